@@ -10,12 +10,12 @@ use embedded_graphics::{
     mono_font::{ascii::FONT_10X20, MonoTextStyle},
     pixelcolor::{BinaryColor, Rgb565, Rgb888},
     prelude::*,
-    primitives::{Circle, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, StrokeAlignment},
+    primitives::{Circle, PrimitiveStyle, PrimitiveStyleBuilder, StrokeAlignment},
     text::{Alignment, Baseline, Text, TextStyle, TextStyleBuilder},
 };
 use embedded_graphics_simulator::{
-    sdl2::MouseButton, BinaryColorTheme, MultiWindow, OutputSettings, OutputSettingsBuilder,
-    SimulatorDisplay, SimulatorEvent,
+    sdl2::MouseButton, BinaryColorTheme, DisplayLayout, MultiWindow, OutputSettings,
+    OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent,
 };
 
 const OLED_TEXT: MonoTextStyle<BinaryColor> = MonoTextStyle::new(&FONT_10X20, BinaryColor::On);
@@ -26,16 +26,6 @@ const CENTERED: TextStyle = TextStyleBuilder::new()
     .baseline(Baseline::Middle)
     .build();
 
-/// Determines the position of a display.
-fn display_offset(window_size: Size, display_size: Size, anchor_point: AnchorPoint) -> Point {
-    // Position displays in a rectangle that is 20px than the the window.
-    let layout_rect = Rectangle::new(Point::zero(), window_size).offset(-20);
-
-    // Resize the rectangle to the display size to determine the offset from the
-    // top left corner of the window to the top left corner of the display.
-    layout_rect.resized(display_size, anchor_point).top_left
-}
-
 fn main() -> Result<(), core::convert::Infallible> {
     // Create three simulated monochrome 128x64 OLED displays.
 
@@ -60,44 +50,42 @@ fn main() -> Result<(), core::convert::Infallible> {
     let mut tft: SimulatorDisplay<Rgb565> = SimulatorDisplay::new(Size::new(320, 240));
     tft.clear(Rgb565::new(5, 10, 5)).unwrap();
 
-    Text::with_text_style(
-        &format!("Draw here"),
-        tft.bounding_box().center(),
-        TFT_TEXT,
-        CENTERED,
-    )
-    .draw(&mut tft)
-    .unwrap();
+    Text::with_text_style("Draw here", tft.bounding_box().center(), TFT_TEXT, CENTERED)
+        .draw(&mut tft)
+        .unwrap();
 
     // The simulated displays can now be added to common simulator window.
-
-    let window_size = Size::new(1300, 500);
-    let mut window = MultiWindow::new("Multiple displays example", window_size);
-    window.clear(Rgb888::CSS_DIM_GRAY);
+    // A layout is used to position the displays in the window.
 
     let oled_settings = OutputSettingsBuilder::new()
         .theme(BinaryColorTheme::OledBlue)
         .scale(2)
         .build();
-    let oled_size = oled_displays[0].output_size(&oled_settings);
+    let tft_settings = OutputSettings::default();
 
-    for (oled, anchor) in oled_displays.iter().zip(
-        [
+    let mut layout = DisplayLayout::anchored(Size::new(1300, 500)).margin(20);
+    for (i, (oled, anchor)) in oled_displays
+        .iter()
+        .zip([
             AnchorPoint::TopLeft,
             AnchorPoint::TopCenter,
             AnchorPoint::TopRight,
-        ]
-        .into_iter(),
-    ) {
-        let offset = display_offset(window_size, oled_size, anchor);
-        window.add_display(&oled, offset, &oled_settings);
+        ])
+        .enumerate()
+    {
+        layout = layout
+            .display(oled, &oled_settings)
+            .anchor(anchor)
+            .caption(&format!("OLED {i}"));
     }
+    layout = layout
+        .display(&tft, &tft_settings)
+        .anchor(AnchorPoint::BottomCenter)
+        .caption("TFT");
 
-    let tft_settings = OutputSettings::default();
-    let tft_size = tft.output_size(&tft_settings);
-    let tft_offset = display_offset(window_size, tft_size, AnchorPoint::BottomCenter);
-
-    window.add_display(&tft, tft_offset, &tft_settings);
+    let mut window = MultiWindow::new("Multiple displays example", layout.size());
+    window.clear(Rgb888::CSS_DIM_GRAY);
+    window.add_layout(&layout);
 
     let border_style = PrimitiveStyleBuilder::new()
         .stroke_width(5)
@@ -117,7 +105,7 @@ fn main() -> Result<(), core::convert::Infallible> {
 
         for event in window.events() {
             match event {
-                SimulatorEvent::MouseMove { point } => {
+                SimulatorEvent::MouseMove { point, .. } => {
                     // Mouse events use the window coordinate system.
                     // `translate_mouse_position` can be used to translate the
                     // mouse position into the display coordinate system.
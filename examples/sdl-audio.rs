@@ -39,14 +39,18 @@ fn main() -> Result<(), core::convert::Infallible> {
         samples: Some(32),
     };
 
+    let output_settings = OutputSettingsBuilder::new()
+        .scale(4)
+        .theme(embedded_graphics_simulator::BinaryColorTheme::OledWhite)
+        .build();
+
+    let mut window = Window::new("Simulator audio example", &output_settings);
+
     // Initialize the SDL audio subsystem.
     //
-    // `sdl2` allows multiple instances of the SDL context to exist, which makes
-    // it possible to access SDL subsystems which aren't used by the simulator.
-    // But keep in mind that only one `EventPump` can exists and the simulator
-    // window creation will fail if the `EventPump` is claimed in advance.
-    let sdl = sdl2::init().unwrap();
-    let audio_subsystem = sdl.audio().unwrap();
+    // The audio subsystem is accessed through the SDL context of the simulator
+    // window, which makes sure that both use the same context.
+    let audio_subsystem = window.sdl_context().audio().unwrap();
 
     // Start audio playback by opening the device and setting the custom callback.
     let audio_device = audio_subsystem
@@ -54,13 +58,6 @@ fn main() -> Result<(), core::convert::Infallible> {
         .unwrap();
     audio_device.resume();
 
-    let output_settings = OutputSettingsBuilder::new()
-        .scale(4)
-        .theme(embedded_graphics_simulator::BinaryColorTheme::OledWhite)
-        .build();
-
-    let mut window = Window::new("Simulator audio example", &output_settings);
-
     let text_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
     let text_position = Point::new(25, 30);
     let text = Text::new("Press space...", text_position, text_style);
@@ -79,14 +76,14 @@ fn main() -> Result<(), core::convert::Infallible> {
                     gate.store(true, Ordering::SeqCst);
                     display.clear(BinaryColor::On).unwrap();
                 }
-                SimulatorEvent::KeyUp { keycode, .. } => match keycode {
-                    Keycode::Space => {
-                        gate.store(false, Ordering::SeqCst);
-                        display.clear(BinaryColor::Off).unwrap();
-                        text.draw(&mut display).unwrap();
-                    }
-                    _ => {}
-                },
+                SimulatorEvent::KeyUp {
+                    keycode: Keycode::Space,
+                    ..
+                } => {
+                    gate.store(false, Ordering::SeqCst);
+                    display.clear(BinaryColor::Off).unwrap();
+                    text.draw(&mut display).unwrap();
+                }
                 _ => {}
             }
         }
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use embedded_graphics::{
+    geometry::AnchorPoint, pixelcolor::Rgb888, prelude::*, primitives::Rectangle,
+};
+
+use crate::{
+    widgets::{ButtonArea, ButtonShape},
+    OutputImage,
+};
+
+/// Device bezel.
+///
+/// A bezel is an image of the device, like an enclosure, a PCB module or a
+/// watch case, which is shown around the simulated display. The display is
+/// drawn centered inside the display area of the bezel image.
+///
+/// Buttons can be added to the bezel to make hardware buttons, which are part
+/// of the bezel image, clickable. Clicking a button with the left mouse button
+/// generates [`VirtualButtonDown`] and [`VirtualButtonUp`] events instead of
+/// mouse events.
+///
+/// A bezel can be used with [`Window::set_bezel`](crate::Window::set_bezel)
+/// and [`MultiWindow::add_bezel`](crate::MultiWindow::add_bezel).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+/// use embedded_graphics_simulator::{Bezel, OutputSettings, SimulatorDisplay, Window};
+///
+/// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+///
+/// let bezel = Bezel::load_png(
+///     "device.png",
+///     Rectangle::new(Point::new(40, 30), Size::new(128, 64)),
+/// )
+/// .unwrap()
+/// .button(0, Rectangle::new(Point::new(180, 40), Size::new(20, 20)))
+/// .button(1, Rectangle::new(Point::new(180, 70), Size::new(20, 20)));
+///
+/// let mut window = Window::new("Device", &OutputSettings::default());
+/// window.set_bezel(Some(bezel));
+/// window.update(&display);
+/// ```
+///
+/// [`VirtualButtonDown`]: crate::SimulatorEvent::VirtualButtonDown
+/// [`VirtualButtonUp`]: crate::SimulatorEvent::VirtualButtonUp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bezel {
+    image: OutputImage<Rgb888>,
+    display_area: Rectangle,
+    buttons: Vec<ButtonArea>,
+}
+
+impl Bezel {
+    /// Creates a new bezel from an image.
+    ///
+    /// The display area is the area of the bezel image in which the display is
+    /// shown.
+    pub fn new(image: OutputImage<Rgb888>, display_area: Rectangle) -> Self {
+        Self {
+            image,
+            display_area,
+            buttons: Vec::new(),
+        }
+    }
+
+    /// Creates a new bezel from a PNG file.
+    ///
+    /// The display area is the area of the bezel image in which the display is
+    /// shown.
+    pub fn load_png<P: AsRef<Path>>(path: P, display_area: Rectangle) -> image::ImageResult<Self> {
+        OutputImage::load_png(path).map(|image| Self::new(image, display_area))
+    }
+
+    /// Adds a clickable button.
+    ///
+    /// The area is specified in bezel image coordinates. The ID is used in the
+    /// generated button events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a button with the same ID was already added.
+    pub fn button(mut self, id: u32, area: Rectangle) -> Self {
+        assert!(
+            !self.buttons.iter().any(|button| button.id == id),
+            "button ID is already used"
+        );
+
+        self.buttons.push(ButtonArea {
+            id,
+            shape: ButtonShape::Rectangle(area),
+        });
+        self
+    }
+
+    /// Returns the size of the bezel image.
+    pub fn size(&self) -> Size {
+        self.image.size()
+    }
+
+    /// Returns the area of the bezel image in which the display is shown.
+    pub fn display_area(&self) -> Rectangle {
+        self.display_area
+    }
+
+    /// Returns the bezel image.
+    pub(crate) fn image(&self) -> &OutputImage<Rgb888> {
+        &self.image
+    }
+
+    /// Returns the buttons.
+    #[cfg(feature = "with-sdl")]
+    pub(crate) fn buttons(&self) -> &[ButtonArea] {
+        &self.buttons
+    }
+
+    /// Returns the offset of a display with the given output size.
+    ///
+    /// The display is centered inside the display area.
+    pub(crate) fn display_offset(&self, output_size: Size) -> Point {
+        self.display_area
+            .resized(output_size, AnchorPoint::Center)
+            .top_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_offset() {
+        let bezel = Bezel::new(
+            OutputImage::new(Size::new(100, 80)),
+            Rectangle::new(Point::new(10, 20), Size::new(64, 32)),
+        );
+
+        assert_eq!(bezel.display_offset(Size::new(64, 32)), Point::new(10, 20));
+        assert_eq!(bezel.display_offset(Size::new(60, 30)), Point::new(12, 21));
+    }
+}
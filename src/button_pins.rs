@@ -0,0 +1,319 @@
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use embedded_hal::digital::{ErrorType, InputPin};
+
+use crate::{
+    sdl2::{Keycode, MouseButton},
+    SimulatorEvent,
+};
+
+/// Number of level changes that are simulated during contact bounce.
+const BOUNCE_TRANSITIONS: u32 = 5;
+
+/// Simulated buttons.
+///
+/// `ButtonPins` creates [`ButtonPin`]s, which implement the `embedded-hal`
+/// [`InputPin`] trait and are bound to a keyboard key or a mouse button. The
+/// state of the pins is updated by passing the [`SimulatorEvent`]s of the
+/// simulator window to [`handle_event`](Self::handle_event). This makes it
+/// possible to run code that reads buttons through `embedded-hal` unchanged in
+/// the simulator.
+///
+/// By default a pin is high while the bound key or button is pressed. Use
+/// [`ButtonPin::active_low`] to simulate buttons with a pull-up resistor.
+///
+/// This type is only available if the `embedded-hal` feature is enabled.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{
+///     sdl2::Keycode, ButtonPins, OutputSettings, SimulatorDisplay, SimulatorEvent, Window,
+/// };
+/// use embedded_hal::digital::InputPin;
+///
+/// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+/// let mut window = Window::new("Buttons", &OutputSettings::default());
+///
+/// let mut buttons = ButtonPins::new();
+/// let mut ok_pin = buttons.key(Keycode::Return).active_low();
+///
+/// 'running: loop {
+///     window.update(&display);
+///
+///     for event in window.events() {
+///         buttons.handle_event(&event);
+///
+///         if event == SimulatorEvent::Quit {
+///             break 'running;
+///         }
+///     }
+///
+///     if ok_pin.is_low().unwrap() {
+///         // OK button is pressed
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct ButtonPins {
+    bindings: Vec<(Binding, Arc<PinState>)>,
+    bounce: Duration,
+}
+
+impl ButtonPins {
+    /// Creates a new set of simulated buttons.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new set of simulated buttons with contact bounce.
+    ///
+    /// After each press or release the level of the pins toggles multiple
+    /// times during the given duration before it settles. This can be used to
+    /// test debouncing code.
+    pub fn with_bounce(bounce: Duration) -> Self {
+        Self {
+            bindings: Vec::new(),
+            bounce,
+        }
+    }
+
+    /// Creates a pin that is bound to a keyboard key.
+    pub fn key(&mut self, keycode: Keycode) -> ButtonPin {
+        self.add_binding(Binding::Key(keycode))
+    }
+
+    /// Creates a pin that is bound to a mouse button.
+    pub fn mouse_button(&mut self, mouse_button: MouseButton) -> ButtonPin {
+        self.add_binding(Binding::MouseButton(mouse_button))
+    }
+
+    fn add_binding(&mut self, binding: Binding) -> ButtonPin {
+        let state = Arc::new(PinState::new(self.bounce));
+        self.bindings.push((binding, Arc::clone(&state)));
+
+        ButtonPin {
+            state,
+            active_low: false,
+        }
+    }
+
+    /// Updates the pin states based on a simulator event.
+    ///
+    /// Events that don't affect any of the bound keys or buttons are ignored.
+    pub fn handle_event(&mut self, event: &SimulatorEvent) {
+        let (binding, pressed) = match *event {
+            SimulatorEvent::KeyDown {
+                keycode,
+                repeat: false,
+                ..
+            } => (Binding::Key(keycode), true),
+            SimulatorEvent::KeyUp { keycode, .. } => (Binding::Key(keycode), false),
+            SimulatorEvent::MouseButtonDown { mouse_btn, .. } => {
+                (Binding::MouseButton(mouse_btn), true)
+            }
+            SimulatorEvent::MouseButtonUp { mouse_btn, .. } => {
+                (Binding::MouseButton(mouse_btn), false)
+            }
+            _ => return,
+        };
+
+        self.set_pressed(binding, pressed);
+    }
+
+    /// Releases all buttons.
+    ///
+    /// This can be used to prevent stuck buttons if the key or mouse button up
+    /// events are lost, for example because the window lost focus.
+    pub fn release_all(&mut self) {
+        for (_, state) in &self.bindings {
+            state.set_pressed(false);
+        }
+    }
+
+    fn set_pressed(&mut self, binding: Binding, pressed: bool) {
+        for (_, state) in self.bindings.iter().filter(|(b, _)| *b == binding) {
+            state.set_pressed(pressed);
+        }
+    }
+}
+
+/// Simulated button pin.
+///
+/// See [`ButtonPins`] for more details.
+#[derive(Debug, Clone)]
+pub struct ButtonPin {
+    state: Arc<PinState>,
+    active_low: bool,
+}
+
+impl ButtonPin {
+    /// Inverts the pin level.
+    ///
+    /// An active low pin is low while the bound key or button is pressed.
+    pub fn active_low(mut self) -> Self {
+        self.active_low = true;
+
+        self
+    }
+
+    /// Returns `true` if the bound key or button is pressed.
+    ///
+    /// The returned value includes the simulated contact bounce.
+    pub fn is_pressed(&self) -> bool {
+        self.state.is_pressed(Instant::now())
+    }
+}
+
+impl ErrorType for ButtonPin {
+    type Error = Infallible;
+}
+
+impl InputPin for ButtonPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.is_pressed() != self.active_low)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Binding {
+    Key(Keycode),
+    MouseButton(MouseButton),
+}
+
+#[derive(Debug)]
+struct PinState {
+    bounce: Duration,
+    inner: Mutex<PinStateInner>,
+}
+
+#[derive(Debug, Default)]
+struct PinStateInner {
+    pressed: bool,
+    changed_at: Option<Instant>,
+}
+
+impl PinState {
+    fn new(bounce: Duration) -> Self {
+        Self {
+            bounce,
+            inner: Mutex::default(),
+        }
+    }
+
+    fn set_pressed(&self, pressed: bool) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.pressed != pressed {
+            inner.pressed = pressed;
+            inner.changed_at = Some(Instant::now());
+        }
+    }
+
+    fn is_pressed(&self, now: Instant) -> bool {
+        let inner = self.inner.lock().unwrap();
+
+        let elapsed = match inner.changed_at {
+            Some(changed_at) => now.saturating_duration_since(changed_at),
+            None => return inner.pressed,
+        };
+
+        if elapsed >= self.bounce {
+            return inner.pressed;
+        }
+
+        // Alternate between the old and the new state during the bounce time.
+        let transition_time = self.bounce / BOUNCE_TRANSITIONS;
+        let transition = elapsed.as_nanos() / transition_time.as_nanos().max(1);
+
+        inner.pressed != (transition % 2 == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sdl2::Mod;
+
+    fn key_down(keycode: Keycode) -> SimulatorEvent {
+        SimulatorEvent::KeyDown {
+            keycode,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        }
+    }
+
+    fn key_up(keycode: Keycode) -> SimulatorEvent {
+        SimulatorEvent::KeyUp {
+            keycode,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn key_pins() {
+        let mut buttons = ButtonPins::new();
+        let mut a = buttons.key(Keycode::A);
+        let mut b = buttons.key(Keycode::B).active_low();
+
+        assert_eq!(a.is_high(), Ok(false));
+        assert_eq!(b.is_high(), Ok(true));
+
+        buttons.handle_event(&key_down(Keycode::A));
+        assert_eq!(a.is_high(), Ok(true));
+        assert_eq!(b.is_high(), Ok(true));
+
+        buttons.handle_event(&key_down(Keycode::B));
+        assert_eq!(b.is_low(), Ok(true));
+
+        buttons.handle_event(&key_up(Keycode::A));
+        assert_eq!(a.is_high(), Ok(false));
+
+        buttons.release_all();
+        assert_eq!(b.is_high(), Ok(true));
+    }
+
+    #[test]
+    fn mouse_button_pin() {
+        let mut buttons = ButtonPins::new();
+        let mut pin = buttons.mouse_button(MouseButton::Right);
+
+        buttons.handle_event(&SimulatorEvent::MouseButtonDown {
+            mouse_btn: MouseButton::Left,
+            point: Default::default(),
+        });
+        assert_eq!(pin.is_high(), Ok(false));
+
+        buttons.handle_event(&SimulatorEvent::MouseButtonDown {
+            mouse_btn: MouseButton::Right,
+            point: Default::default(),
+        });
+        assert_eq!(pin.is_high(), Ok(true));
+    }
+
+    #[test]
+    fn bounce() {
+        let bounce = Duration::from_millis(10);
+        let state = PinState::new(bounce);
+
+        state.set_pressed(true);
+        let changed_at = state.inner.lock().unwrap().changed_at.unwrap();
+
+        let transition_time = bounce / BOUNCE_TRANSITIONS;
+        assert!(state.is_pressed(changed_at));
+        assert!(!state.is_pressed(changed_at + transition_time));
+        assert!(state.is_pressed(changed_at + transition_time * 2));
+        assert!(state.is_pressed(changed_at + bounce));
+    }
+}
@@ -0,0 +1,738 @@
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+
+use crate::{
+    display::bounding_box_union,
+    layout::{caption_area, caption_text, DisplayLayout},
+    widgets::{Led, VirtualButton},
+    OutputImage, OutputSettings, SimulatorDisplay,
+};
+
+/// Composition of multiple displays.
+///
+/// A display composition combines multiple [`SimulatorDisplay`]s into a single
+/// [`OutputImage`]. It is used by [`MultiWindow`](crate::MultiWindow) to draw
+/// the window content, but can also be used without a window, for example to
+/// create screenshots of multi display setups in tests. A display composition
+/// is also available if the `with-sdl` feature is disabled.
+///
+/// Displays are added to the composition by using
+/// [`add_display`](Self::add_display) or [`add_layout`](Self::add_layout).
+/// Changes to the displays are applied to the composited image by calling
+/// [`update_display`](Self::update_display).
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{
+///     pixelcolor::{BinaryColor, Rgb888},
+///     prelude::*,
+/// };
+/// use embedded_graphics_simulator::{
+///     DisplayComposition, DisplayLayout, OutputSettings, SimulatorDisplay,
+/// };
+///
+/// let mut display1 = SimulatorDisplay::<BinaryColor>::new(Size::new(32, 16));
+/// let display2 = SimulatorDisplay::<BinaryColor>::new(Size::new(32, 16));
+/// display1.clear(BinaryColor::On).unwrap();
+///
+/// let layout = DisplayLayout::row()
+///     .spacing(4)
+///     .display(&display1, &OutputSettings::default())
+///     .display(&display2, &OutputSettings::default());
+///
+/// let mut composition = DisplayComposition::new(layout.size());
+/// composition.clear(Rgb888::CSS_DIM_GRAY);
+/// composition.add_layout(&layout);
+/// composition.update_display(&display1);
+/// composition.update_display(&display2);
+///
+/// let output_image = composition.output_image();
+/// assert_eq!(output_image.size(), Size::new(68, 16));
+/// // example: output_image.save_png("composition.png")?;
+/// ```
+pub struct DisplayComposition {
+    framebuffer: OutputImage<Rgb888>,
+    /// Background, which is shown in regions that aren't covered by a display.
+    background: OutputImage<Rgb888>,
+    /// Displays sorted by the z-order.
+    displays: Vec<DisplayEntry>,
+    next_handle: usize,
+    caption_color: Rgb888,
+    buttons: Vec<VirtualButton>,
+    leds: Vec<Led>,
+    /// Area of the framebuffer that was changed since the last call to `take_dirty_area`.
+    dirty_area: Option<Rectangle>,
+}
+
+impl DisplayComposition {
+    /// Creates a new display composition.
+    pub fn new(size: Size) -> Self {
+        Self {
+            framebuffer: OutputImage::new(size),
+            background: OutputImage::new(size),
+            displays: Vec::new(),
+            next_handle: 0,
+            caption_color: Rgb888::WHITE,
+            buttons: Vec::new(),
+            leds: Vec::new(),
+            dirty_area: None,
+        }
+    }
+
+    /// Returns the composited image.
+    pub fn output_image(&self) -> &OutputImage<Rgb888> {
+        &self.framebuffer
+    }
+
+    /// Adds a display to the composition.
+    ///
+    /// The same display can be added multiple times, for example to show it
+    /// with different output settings. The returned handle identifies this
+    /// instance of the display in the composition.
+    ///
+    /// The display is shown after the next call to
+    /// [`update_display`](Self::update_display).
+    pub fn add_display<C>(
+        &mut self,
+        display: &SimulatorDisplay<C>,
+        offset: Point,
+        output_settings: &OutputSettings,
+    ) -> DisplayHandle {
+        self.add_display_entry(display.id, display.size(), offset, output_settings, None)
+    }
+
+    /// Adds all displays in a layout to the composition.
+    ///
+    /// The returned handles are in the same order in which the displays were
+    /// added to the layout. Use [`DisplayLayout::size`] to create a composition
+    /// that is large enough to show all displays.
+    pub fn add_layout(&mut self, layout: &DisplayLayout) -> Vec<DisplayHandle> {
+        layout
+            .items()
+            .iter()
+            .zip(layout.offsets())
+            .map(|(item, offset)| {
+                self.add_display_entry(
+                    item.display_id,
+                    item.display_size,
+                    offset,
+                    &item.output_settings,
+                    item.caption.clone(),
+                )
+            })
+            .collect()
+    }
+
+    fn add_display_entry(
+        &mut self,
+        display_id: usize,
+        size: Size,
+        offset: Point,
+        output_settings: &OutputSettings,
+        caption: Option<String>,
+    ) -> DisplayHandle {
+        let handle = DisplayHandle(self.next_handle);
+        self.next_handle += 1;
+
+        self.displays.push(DisplayEntry {
+            handle,
+            display_id,
+            size,
+            offset,
+            output_settings: *output_settings,
+            z_order: 0,
+            caption,
+            layer: None,
+            dirty_generation: 0,
+        });
+        self.sort_displays();
+
+        // The caption is part of the background and can be shown
+        // before the display is drawn.
+        if let Some(entry) = self.displays.iter().find(|entry| entry.handle == handle) {
+            let area = entry.bounding_box();
+            self.compose(area);
+        }
+
+        handle
+    }
+
+    /// Sets the caption of a display.
+    ///
+    /// The caption is shown centered below the display. Passing `None` removes
+    /// the caption.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this composition.
+    pub fn set_caption(&mut self, handle: DisplayHandle, caption: Option<&str>) {
+        let index = self.display_index(handle);
+        let old_area = self.displays[index].bounding_box();
+
+        self.displays[index].caption = caption.map(String::from);
+        let new_area = self.displays[index].bounding_box();
+
+        self.compose(old_area);
+        self.compose(new_area);
+    }
+
+    /// Sets the text color of the display captions and widget labels.
+    ///
+    /// The default caption color is white.
+    pub fn set_caption_color(&mut self, color: Rgb888) {
+        self.caption_color = color;
+        self.compose(self.framebuffer.bounding_box());
+    }
+
+    /// Removes a display from the composition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this composition.
+    pub fn remove_display(&mut self, handle: DisplayHandle) {
+        let index = self.display_index(handle);
+        let entry = self.displays.remove(index);
+
+        self.compose(entry.bounding_box());
+    }
+
+    /// Moves a display to a new offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this composition.
+    pub fn set_offset(&mut self, handle: DisplayHandle, offset: Point) {
+        let index = self.display_index(handle);
+        let old_area = self.displays[index].bounding_box();
+
+        self.displays[index].offset = offset;
+        let new_area = self.displays[index].bounding_box();
+
+        self.compose(old_area);
+        self.compose(new_area);
+    }
+
+    /// Changes the output settings of a display.
+    ///
+    /// The display is redrawn with the new settings on the next call to
+    /// [`update_display`](Self::update_display). Until then the area of the
+    /// display shows the background.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this composition.
+    pub fn set_output_settings(&mut self, handle: DisplayHandle, output_settings: &OutputSettings) {
+        let index = self.display_index(handle);
+        let old_area = self.displays[index].bounding_box();
+
+        let entry = &mut self.displays[index];
+        entry.output_settings = *output_settings;
+        entry.layer = None;
+
+        self.compose(old_area);
+    }
+
+    /// Sets the z-order of a display.
+    ///
+    /// Displays with a higher z-order are drawn on top of displays with a
+    /// lower z-order. Displays with the same z-order are drawn in the order in
+    /// which they were added. The default z-order is `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this composition.
+    pub fn set_z_order(&mut self, handle: DisplayHandle, z_order: i32) {
+        let index = self.display_index(handle);
+        self.displays[index].z_order = z_order;
+        let area = self.displays[index].bounding_box();
+
+        self.sort_displays();
+        self.compose(area);
+    }
+
+    /// Returns the handles of all displays in the composition.
+    ///
+    /// The handles are sorted by the z-order, starting with the bottom display.
+    pub fn displays(&self) -> impl Iterator<Item = DisplayHandle> + '_ {
+        self.displays.iter().map(|entry| entry.handle)
+    }
+
+    /// Fills the background with the given color.
+    ///
+    /// This method can be used to set the background color for the regions of
+    /// the composition that aren't covered by a display.
+    pub fn clear(&mut self, color: Rgb888) {
+        self.background.clear(color).unwrap();
+        self.compose(self.framebuffer.bounding_box());
+    }
+
+    /// Draws an image onto the background.
+    ///
+    /// This method can be used to show a picture of the device, for example a
+    /// [`Bezel`](crate::Bezel), behind the displays.
+    pub fn draw_background_image(&mut self, image: &OutputImage<Rgb888>, position: Point) {
+        let area = self
+            .background
+            .copy_area(image, &image.bounding_box(), position);
+        self.compose(area);
+    }
+
+    /// Adds a virtual button.
+    ///
+    /// Buttons are drawn on top of the background and below the displays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a button with the same ID was already added.
+    pub fn add_button(&mut self, button: VirtualButton) {
+        assert!(
+            !self.buttons.iter().any(|other| other.id == button.id),
+            "button ID is already used"
+        );
+
+        let area = button.bounding_box();
+        self.buttons.push(button);
+        self.compose(area);
+    }
+
+    /// Adds an LED indicator.
+    ///
+    /// LEDs are drawn on top of the background and below the displays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an LED with the same ID was already added.
+    pub fn add_led(&mut self, led: Led) {
+        assert!(
+            !self.leds.iter().any(|other| other.id == led.id),
+            "LED ID is already used"
+        );
+
+        let area = led.bounding_box();
+        self.leds.push(led);
+        self.compose(area);
+    }
+
+    /// Sets the color of an LED.
+    ///
+    /// The LED is only redrawn if the color has changed, which makes it
+    /// possible to call this method in every frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no LED with the given ID was added to this composition.
+    pub fn set_led_color(&mut self, id: u32, color: Rgb888) {
+        let led = self
+            .leds
+            .iter_mut()
+            .find(|led| led.id == id)
+            .expect("LED ID isn't part of this composition");
+
+        if led.color != color {
+            led.color = color;
+            let area = led.bounding_box();
+            self.compose(area);
+        }
+    }
+
+    /// Updates one display.
+    ///
+    /// If the display was added multiple times, all instances are updated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display wasn't added with [`add_display`](Self::add_display).
+    pub fn update_display<C>(&mut self, display: &SimulatorDisplay<C>)
+    where
+        C: PixelColor + Into<Rgb888> + From<Rgb888>,
+    {
+        assert!(
+            self.displays
+                .iter()
+                .any(|entry| entry.display_id == display.id),
+            "update_display called for a display that hasn't been added with add_display"
+        );
+
+        let mut changed_areas = Vec::new();
+        for entry in self
+            .displays
+            .iter_mut()
+            .filter(|entry| entry.display_id == display.id)
+        {
+            entry.size = display.size();
+
+            // Only the changed part of the display needs to be redrawn if the
+            // display was already drawn before.
+            let dirty_area = display.take_window_dirty_area(&mut entry.dirty_generation);
+
+            let output_area = match &mut entry.layer {
+                Some(layer) => match dirty_area {
+                    Some(area) => layer.draw_display_area(
+                        display,
+                        Point::zero(),
+                        &entry.output_settings,
+                        &area,
+                    ),
+                    None => continue,
+                },
+                None => {
+                    let mut layer = OutputImage::new(display.output_size(&entry.output_settings));
+                    layer.draw_display(display, Point::zero(), &entry.output_settings);
+                    let output_area = layer.bounding_box();
+                    entry.layer = Some(layer);
+
+                    output_area
+                }
+            };
+
+            changed_areas.push(Rectangle::new(
+                output_area.top_left + entry.offset,
+                output_area.size,
+            ));
+        }
+
+        for area in changed_areas {
+            self.compose(area);
+        }
+    }
+
+    /// Redraws an area of the framebuffer from the background and the displays.
+    fn compose(&mut self, area: Rectangle) {
+        let area = area.intersection(&self.framebuffer.bounding_box());
+        if area.is_zero_sized() {
+            return;
+        }
+
+        self.framebuffer
+            .copy_area(&self.background, &area, Point::zero());
+
+        for button in &self.buttons {
+            button.draw(&mut self.framebuffer.clipped(&area), self.caption_color);
+        }
+
+        for led in &self.leds {
+            led.draw(&mut self.framebuffer.clipped(&area), self.caption_color);
+        }
+
+        for entry in &self.displays {
+            if let Some(caption) = &entry.caption {
+                caption_text(caption, &entry.display_area(), self.caption_color)
+                    .draw(&mut self.framebuffer.clipped(&area))
+                    .unwrap();
+            }
+
+            if let Some(layer) = &entry.layer {
+                let layer_area = Rectangle::new(area.top_left - entry.offset, area.size);
+                self.framebuffer.copy_area(layer, &layer_area, entry.offset);
+            }
+        }
+
+        self.mark_dirty(area);
+    }
+
+    fn sort_displays(&mut self) {
+        self.displays
+            .sort_by_key(|entry| (entry.z_order, entry.handle));
+    }
+
+    pub(crate) fn display_index(&self, handle: DisplayHandle) -> usize {
+        self.displays
+            .iter()
+            .position(|entry| entry.handle == handle)
+            .expect("display handle isn't part of this composition")
+    }
+
+    /// Returns and resets the area that was changed since the last call.
+    #[cfg(any(feature = "with-sdl", test))]
+    pub(crate) fn take_dirty_area(&mut self) -> Option<Rectangle> {
+        self.dirty_area.take()
+    }
+
+    fn mark_dirty(&mut self, area: Rectangle) {
+        if area.is_zero_sized() {
+            return;
+        }
+
+        self.dirty_area = Some(match &self.dirty_area {
+            Some(dirty_area) => bounding_box_union(dirty_area, &area),
+            None => area,
+        });
+    }
+
+    /// Translate a mouse position into display coordinates.
+    ///
+    /// Returns the corresponding position in the display coordinate system if
+    /// the mouse is inside the display area, otherwise `None` is returned. If
+    /// the display was added multiple times the topmost instance under the
+    /// mouse is used.
+    pub fn translate_mouse_position<C>(
+        &self,
+        display: &SimulatorDisplay<C>,
+        position: Point,
+    ) -> Option<Point> {
+        assert!(
+            self.displays
+                .iter()
+                .any(|entry| entry.display_id == display.id),
+            "translate_mouse_position called for a display that hasn't been added with add_display"
+        );
+
+        self.displays
+            .iter()
+            .rev()
+            .filter(|entry| entry.display_id == display.id)
+            .find_map(|entry| entry.translate_position(position))
+    }
+
+    /// Translate a mouse position into the coordinates of a display instance.
+    ///
+    /// Returns the corresponding position in the display coordinate system if
+    /// the mouse is inside the display area, otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this composition.
+    pub fn translate_mouse_position_for(
+        &self,
+        handle: DisplayHandle,
+        position: Point,
+    ) -> Option<Point> {
+        self.displays[self.display_index(handle)].translate_position(position)
+    }
+
+    /// Returns the topmost display at a position.
+    #[cfg(feature = "with-sdl")]
+    pub(crate) fn display_at(&self, position: Point) -> Option<&DisplayEntry> {
+        self.displays
+            .iter()
+            .rev()
+            .find(|entry| entry.translate_position(position).is_some())
+    }
+
+    /// Returns the areas that are covered by displays.
+    #[cfg(feature = "with-sdl")]
+    pub(crate) fn display_areas(&self) -> Vec<Rectangle> {
+        self.displays
+            .iter()
+            .map(|entry| entry.display_area())
+            .collect()
+    }
+}
+
+/// Handle of a display in a [`DisplayComposition`] or [`MultiWindow`](crate::MultiWindow).
+///
+/// Handles are returned by [`DisplayComposition::add_display`] and
+/// [`MultiWindow::add_display`](crate::MultiWindow::add_display) and are unique
+/// for each added display, even if the same display is added multiple times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisplayHandle(usize);
+
+pub(crate) struct DisplayEntry {
+    pub(crate) handle: DisplayHandle,
+    /// ID of the `SimulatorDisplay`.
+    display_id: usize,
+    /// Size of the display in display pixels.
+    size: Size,
+    offset: Point,
+    output_settings: OutputSettings,
+    z_order: i32,
+    caption: Option<String>,
+    /// Rendered display, which is `None` until the display is drawn.
+    layer: Option<OutputImage<Rgb888>>,
+    /// Dirty area generation of the display when the layer was last updated.
+    dirty_generation: u64,
+}
+
+impl DisplayEntry {
+    /// Returns the area of the composition that is covered by the display.
+    fn display_area(&self) -> Rectangle {
+        Rectangle::new(self.offset, self.output_settings.output_size(self.size))
+    }
+
+    /// Returns the area of the composition that is covered by the display and the caption.
+    fn bounding_box(&self) -> Rectangle {
+        let display_area = self.display_area();
+
+        match &self.caption {
+            Some(caption) => {
+                bounding_box_union(&display_area, &caption_area(caption, &display_area))
+            }
+            None => display_area,
+        }
+    }
+
+    fn translate_position(&self, position: Point) -> Option<Point> {
+        self.display_area()
+            .contains(position)
+            .then(|| self.translate_position_unchecked(position))
+    }
+
+    pub(crate) fn translate_position_unchecked(&self, position: Point) -> Point {
+        self.output_settings
+            .output_to_display(position - self.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::{pixelcolor::BinaryColor, primitives::Circle};
+
+    fn pixel(composition: &DisplayComposition, x: u32, y: u32) -> Rgb888 {
+        let pixel = composition.output_image().as_image_buffer()[(x, y)];
+        Rgb888::new(pixel[0], pixel[1], pixel[2])
+    }
+
+    #[test]
+    fn z_order() {
+        let mut bottom = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 4));
+        let top = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 4));
+        bottom.clear(BinaryColor::On).unwrap();
+
+        let mut composition = DisplayComposition::new(Size::new(8, 8));
+        composition.clear(Rgb888::RED);
+        let bottom_handle =
+            composition.add_display(&bottom, Point::zero(), &OutputSettings::default());
+        composition.add_display(&top, Point::new(2, 2), &OutputSettings::default());
+        composition.update_display(&bottom);
+        composition.update_display(&top);
+
+        assert_eq!(pixel(&composition, 1, 1), Rgb888::WHITE);
+        assert_eq!(pixel(&composition, 3, 3), Rgb888::BLACK);
+        assert_eq!(pixel(&composition, 7, 7), Rgb888::RED);
+
+        composition.set_z_order(bottom_handle, 1);
+        assert_eq!(pixel(&composition, 3, 3), Rgb888::WHITE);
+        assert_eq!(
+            composition.displays().next(),
+            Some(DisplayHandle(1)),
+            "displays should be sorted by z-order"
+        );
+    }
+
+    #[test]
+    fn remove_and_move_display() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(2, 2));
+        display.clear(BinaryColor::On).unwrap();
+
+        let mut composition = DisplayComposition::new(Size::new(6, 6));
+        composition.clear(Rgb888::BLUE);
+        let handle = composition.add_display(&display, Point::zero(), &OutputSettings::default());
+        composition.update_display(&display);
+        assert_eq!(pixel(&composition, 0, 0), Rgb888::WHITE);
+
+        composition.set_offset(handle, Point::new(4, 4));
+        assert_eq!(pixel(&composition, 0, 0), Rgb888::BLUE);
+        assert_eq!(pixel(&composition, 5, 5), Rgb888::WHITE);
+        assert_eq!(
+            composition.translate_mouse_position_for(handle, Point::new(5, 4)),
+            Some(Point::new(1, 0))
+        );
+
+        composition.remove_display(handle);
+        assert_eq!(pixel(&composition, 5, 5), Rgb888::BLUE);
+        assert_eq!(composition.displays().count(), 0);
+    }
+
+    #[test]
+    fn display_in_multiple_compositions() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 4));
+
+        let mut first = DisplayComposition::new(Size::new(4, 4));
+        let mut second = DisplayComposition::new(Size::new(4, 4));
+        first.add_display(&display, Point::zero(), &OutputSettings::default());
+        second.add_display(&display, Point::zero(), &OutputSettings::default());
+        first.update_display(&display);
+        second.update_display(&display);
+
+        Pixel(Point::new(1, 2), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+
+        // Both compositions must see the change, regardless of which one is
+        // updated first.
+        first.update_display(&display);
+        second.update_display(&display);
+        assert_eq!(pixel(&first, 1, 2), Rgb888::WHITE);
+        assert_eq!(pixel(&second, 1, 2), Rgb888::WHITE);
+    }
+
+    #[test]
+    fn caption_is_drawn_below_display() {
+        let display = SimulatorDisplay::<BinaryColor>::new(Size::new(40, 10));
+
+        let mut composition = DisplayComposition::new(Size::new(40, 30));
+        composition.clear(Rgb888::BLACK);
+        composition.set_caption_color(Rgb888::GREEN);
+        let handle = composition.add_display(&display, Point::zero(), &OutputSettings::default());
+        composition.set_caption(handle, Some("Test"));
+
+        let caption_pixels = |composition: &DisplayComposition| {
+            (0..40)
+                .flat_map(|x| (10..30).map(move |y| (x, y)))
+                .filter(|(x, y)| pixel(composition, *x, *y) == Rgb888::GREEN)
+                .count()
+        };
+
+        assert_ne!(caption_pixels(&composition), 0);
+
+        composition.set_caption(handle, None);
+        assert_eq!(caption_pixels(&composition), 0);
+    }
+
+    #[test]
+    fn widgets() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 4));
+        display.clear(BinaryColor::On).unwrap();
+
+        let mut composition = DisplayComposition::new(Size::new(40, 20));
+        composition.clear(Rgb888::BLACK);
+        composition.add_button(
+            VirtualButton::rectangle(0, Rectangle::new(Point::zero(), Size::new(8, 8)))
+                .color(Rgb888::BLUE),
+        );
+        composition.add_led(Led::new(0, Circle::new(Point::new(20, 0), 9)));
+
+        // Displays are drawn on top of widgets.
+        composition.add_display(&display, Point::new(4, 4), &OutputSettings::default());
+        composition.update_display(&display);
+
+        assert_eq!(pixel(&composition, 1, 1), Rgb888::BLUE);
+        assert_eq!(pixel(&composition, 5, 5), Rgb888::WHITE);
+        assert_eq!(pixel(&composition, 24, 4), Rgb888::BLACK);
+
+        composition.take_dirty_area();
+        composition.set_led_color(0, Rgb888::GREEN);
+        assert_eq!(pixel(&composition, 24, 4), Rgb888::GREEN);
+        assert_eq!(
+            composition.take_dirty_area(),
+            Some(Rectangle::new(Point::new(20, 0), Size::new(9, 9)))
+        );
+
+        composition.set_led_color(0, Rgb888::GREEN);
+        assert_eq!(composition.take_dirty_area(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "button ID is already used")]
+    fn duplicate_button_id() {
+        let mut composition = DisplayComposition::new(Size::new(40, 20));
+        composition.add_button(VirtualButton::rectangle(
+            0,
+            Rectangle::new(Point::zero(), Size::new(8, 8)),
+        ));
+        composition.add_button(VirtualButton::rectangle(
+            0,
+            Rectangle::new(Point::new(10, 0), Size::new(8, 8)),
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "LED ID is already used")]
+    fn duplicate_led_id() {
+        let mut composition = DisplayComposition::new(Size::new(40, 20));
+        composition.add_led(Led::new(0, Circle::new(Point::zero(), 9)));
+        composition.add_led(Led::new(0, Circle::new(Point::new(10, 0), 9)));
+    }
+}
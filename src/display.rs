@@ -1,33 +1,61 @@
 use std::{
+    cmp::Ordering as CmpOrdering,
+    collections::VecDeque,
     convert::TryFrom,
     fs::File,
+    hash::{Hash, Hasher},
     io::BufReader,
     path::Path,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
 };
 
 use embedded_graphics::{
     pixelcolor::{raw::ToBytes, BinaryColor, Gray8, Rgb888},
     prelude::*,
+    primitives::Rectangle,
 };
 
-use crate::{output_image::OutputImage, output_settings::OutputSettings};
+use crate::{
+    output_image::OutputImage, output_settings::OutputSettings, statistics::DrawStatistics,
+};
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
 /// Simulator display.
-#[derive(Debug, Clone, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug)]
 pub struct SimulatorDisplay<C> {
     size: Size,
     pub(crate) pixels: Box<[C]>,
     pub(crate) id: usize,
+    statistics: Option<DrawStatistics>,
+    dirty_area: DirtyArea,
+}
+
+impl<C: Clone> Clone for SimulatorDisplay<C> {
+    fn clone(&self) -> Self {
+        // The clone gets a new ID, because windows use the ID to identify
+        // displays and the clone can be changed independently of the original.
+        Self {
+            size: self.size,
+            pixels: self.pixels.clone(),
+            id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            statistics: self.statistics.clone(),
+            dirty_area: self.dirty_area.clone(),
+        }
+    }
 }
 
 impl<C: PixelColor> SimulatorDisplay<C> {
     fn new_common(size: Size, pixels: Box<[C]>) -> Self {
         let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
 
-        Self { size, pixels, id }
+        Self {
+            size,
+            pixels,
+            id,
+            statistics: None,
+            dirty_area: DirtyArea::default(),
+        }
     }
 
     /// Creates a new display filled with a color.
@@ -89,7 +117,7 @@ impl<C: PixelColor> SimulatorDisplay<C> {
             .collect::<Vec<_>>()
             .into_boxed_slice();
 
-        if pixels.iter().any(|p| *p == BinaryColor::On) {
+        if pixels.contains(&BinaryColor::On) {
             Some(SimulatorDisplay::new_common(self.size, pixels))
         } else {
             None
@@ -102,8 +130,84 @@ impl<C: PixelColor> SimulatorDisplay<C> {
     /// [`pixel_spacing`](OutputSettings::pixel_spacing) settings to determine
     /// the size of this display in output pixels.
     pub fn output_size(&self, output_settings: &OutputSettings) -> Size {
-        self.size * output_settings.scale
-            + self.size.saturating_sub(Size::new_equal(1)) * output_settings.pixel_spacing
+        output_settings.output_size(self.size)
+    }
+
+    /// Enables the collection of draw statistics.
+    ///
+    /// See [`DrawStatistics`] for more details. Calling this method while the
+    /// statistics are already enabled doesn't reset the collected data.
+    pub fn enable_statistics(&mut self) {
+        if self.statistics.is_none() {
+            self.statistics = Some(DrawStatistics::new(self.size));
+        }
+    }
+
+    /// Disables the collection of draw statistics.
+    pub fn disable_statistics(&mut self) {
+        self.statistics = None;
+    }
+
+    /// Returns the collected draw statistics.
+    ///
+    /// Returns `None` if the statistics weren't enabled by calling
+    /// [`enable_statistics`](Self::enable_statistics).
+    pub fn statistics(&self) -> Option<&DrawStatistics> {
+        self.statistics.as_ref()
+    }
+
+    /// Resets the collected draw statistics.
+    ///
+    /// This method is usually called once per frame, after the statistics of
+    /// the previous frame were evaluated.
+    pub fn reset_statistics(&mut self) {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.reset();
+        }
+    }
+
+    /// Returns the area that was changed since the last call to
+    /// [`reset_dirty_area`](Self::reset_dirty_area).
+    ///
+    /// The dirty area is the bounding box of all pixels that were written by
+    /// [`DrawTarget`] methods, regardless of whether the color of the pixels
+    /// changed. `None` is returned if no pixels were written.
+    ///
+    /// This can be used to check the partial refresh logic of display drivers,
+    /// which only update the changed part of the display.
+    pub fn dirty_area(&self) -> Option<Rectangle> {
+        self.dirty_area.user
+    }
+
+    /// Resets the dirty area.
+    ///
+    /// Resetting the dirty area doesn't affect the window updates, which use
+    /// a separate dirty area internally.
+    pub fn reset_dirty_area(&mut self) {
+        self.dirty_area.user = None;
+    }
+
+    /// Returns the area that was changed since the given generation.
+    ///
+    /// This method is used by the simulator windows to only redraw the changed
+    /// part of the display. Each window keeps track of the last generation it
+    /// has seen, which is updated by this method. Because of this the same
+    /// display can be shown in multiple windows at the same time.
+    pub(crate) fn take_window_dirty_area(&self, generation: &mut u64) -> Option<Rectangle> {
+        self.dirty_area
+            .changes_since(generation, &self.bounding_box())
+    }
+
+    fn mark_dirty(&mut self, area: Rectangle) {
+        self.dirty_area.add(area);
+    }
+
+    fn set_pixel(&mut self, index: usize, color: C) {
+        self.pixels[index] = color;
+
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_write(index);
+        }
     }
 }
 
@@ -264,14 +368,213 @@ impl<C: PixelColor> DrawTarget for SimulatorDisplay<C> {
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_draw_iter();
+        }
+
+        let mut min = Point::new(i32::MAX, i32::MAX);
+        let mut max = Point::new(i32::MIN, i32::MIN);
+
         for Pixel(point, color) in pixels.into_iter() {
             if let Some(index) = self.point_to_index(point) {
-                self.pixels[index] = color;
+                self.set_pixel(index, color);
+
+                min = min.component_min(point);
+                max = max.component_max(point);
             }
         }
 
+        if min.x <= max.x {
+            self.mark_dirty(Rectangle::with_corners(min, max));
+        }
+
         Ok(())
     }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_fill_contiguous();
+        }
+
+        let drawable_area = area.intersection(&self.bounding_box());
+        if drawable_area.is_zero_sized() {
+            return Ok(());
+        }
+
+        // Number of colors in each row of `area` that are outside the display
+        // on the left and on the right side.
+        let area_width = area.size.width as usize;
+        let drawable_width = drawable_area.size.width as usize;
+        let skip_left = (drawable_area.top_left.x - area.top_left.x) as usize;
+        let skip_right = area_width - drawable_width - skip_left;
+
+        self.mark_dirty(drawable_area);
+
+        let drawable_rows = drawable_area.rows();
+        let mut colors = colors.into_iter();
+
+        for y in area.rows() {
+            if y < drawable_rows.start {
+                colors.by_ref().take(area_width).for_each(drop);
+                continue;
+            } else if y >= drawable_rows.end {
+                break;
+            }
+
+            colors.by_ref().take(skip_left).for_each(drop);
+
+            let start = y as usize * self.size.width as usize + drawable_area.top_left.x as usize;
+            let row = &mut self.pixels[start..start + drawable_width];
+
+            let mut written = 0;
+            for (pixel, color) in row.iter_mut().zip(colors.by_ref()) {
+                *pixel = color;
+                written += 1;
+            }
+
+            if let Some(statistics) = &mut self.statistics {
+                statistics.record_writes(start..start + written);
+            }
+
+            if written < drawable_width {
+                break;
+            }
+
+            colors.by_ref().take(skip_right).for_each(drop);
+        }
+
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_fill_solid();
+        }
+
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+
+        self.mark_dirty(area);
+
+        let width = area.size.width as usize;
+        for y in area.rows() {
+            let start = y as usize * self.size.width as usize + area.top_left.x as usize;
+            self.pixels[start..start + width].fill(color);
+
+            if let Some(statistics) = &mut self.statistics {
+                statistics.record_writes(start..start + width);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        if let Some(statistics) = &mut self.statistics {
+            statistics.record_clear();
+            statistics.record_writes(0..self.pixels.len());
+        }
+
+        self.pixels.fill(color);
+        self.mark_dirty(self.bounding_box());
+
+        Ok(())
+    }
+}
+
+/// Maximum number of generations that are stored in the dirty area history.
+const DIRTY_AREA_HISTORY_LEN: usize = 8;
+
+/// Dirty area tracking.
+///
+/// The changes for window updates are stored in generations. A new generation
+/// is started by the first change after a window has checked the display for
+/// changes. The areas of the last generations are kept, which allows multiple
+/// windows to show the same display independently of each other.
+#[derive(Debug, Default)]
+struct DirtyArea {
+    /// Area that was changed since the last `reset_dirty_area` call.
+    user: Option<Rectangle>,
+    /// Current generation.
+    generation: u64,
+    /// Changed areas of the last generations, the last entry belongs to the
+    /// current generation.
+    history: VecDeque<Rectangle>,
+    /// Whether the current generation was seen by a window.
+    ///
+    /// An atomic is used because windows only have shared access to the display.
+    observed: AtomicBool,
+}
+
+impl DirtyArea {
+    fn add(&mut self, area: Rectangle) {
+        self.user = Some(match &self.user {
+            Some(user) => bounding_box_union(user, &area),
+            None => area,
+        });
+
+        let observed = std::mem::take(self.observed.get_mut());
+        match self.history.back_mut() {
+            Some(current) if !observed => *current = bounding_box_union(current, &area),
+            _ => {
+                self.generation += 1;
+                self.history.push_back(area);
+                if self.history.len() > DIRTY_AREA_HISTORY_LEN {
+                    self.history.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Returns the area that was changed since the given generation and
+    /// updates the generation.
+    ///
+    /// The full display area is returned if the changes aren't available in
+    /// the history anymore.
+    fn changes_since(&self, generation: &mut u64, display_area: &Rectangle) -> Option<Rectangle> {
+        self.observed.store(true, Ordering::Relaxed);
+
+        let count = self.generation.checked_sub(*generation);
+        *generation = self.generation;
+
+        match count {
+            Some(0) => None,
+            Some(count) if count as usize <= self.history.len() => self
+                .history
+                .iter()
+                .rev()
+                .take(count as usize)
+                .copied()
+                .reduce(|a, b| bounding_box_union(&a, &b)),
+            _ => Some(*display_area),
+        }
+    }
+}
+
+impl Clone for DirtyArea {
+    fn clone(&self) -> Self {
+        Self {
+            user: self.user,
+            generation: self.generation,
+            history: self.history.clone(),
+            observed: AtomicBool::new(self.observed.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Returns the smallest rectangle that contains both non empty rectangles.
+pub(crate) fn bounding_box_union(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    Rectangle::with_corners(
+        a.top_left.component_min(b.top_left),
+        a.bottom_right()
+            .unwrap()
+            .component_max(b.bottom_right().unwrap()),
+    )
 }
 
 impl<C> OriginDimensions for SimulatorDisplay<C> {
@@ -280,12 +583,38 @@ impl<C> OriginDimensions for SimulatorDisplay<C> {
     }
 }
 
+// The ID, the draw statistics and the dirty area are only used for diagnostics
+// and window updates and are therefore ignored by the following
+// implementations. A clone is equal to the original display, even though it
+// has a different ID.
+
 impl<C: PartialEq> PartialEq for SimulatorDisplay<C> {
     fn eq(&self, other: &Self) -> bool {
         self.size == other.size && self.pixels == other.pixels
     }
 }
 
+impl<C: Eq> Eq for SimulatorDisplay<C> {}
+
+impl<C: PartialOrd> PartialOrd for SimulatorDisplay<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        (self.size, &self.pixels).partial_cmp(&(other.size, &other.pixels))
+    }
+}
+
+impl<C: Ord> Ord for SimulatorDisplay<C> {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        (self.size, &self.pixels).cmp(&(other.size, &other.pixels))
+    }
+}
+
+impl<C: Hash> Hash for SimulatorDisplay<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.pixels.hash(state);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,6 +680,8 @@ mod tests {
             .collect::<Vec<_>>()
             .into_boxed_slice(),
             id: 0,
+            statistics: None,
+            dirty_area: DirtyArea::default(),
         };
 
         let expected = [
@@ -376,6 +707,8 @@ mod tests {
             .collect::<Vec<_>>()
             .into_boxed_slice(),
             id: 0,
+            statistics: None,
+            dirty_area: DirtyArea::default(),
         };
 
         let expected = [
@@ -402,6 +735,8 @@ mod tests {
             .collect::<Vec<_>>()
             .into_boxed_slice(),
             id: 0,
+            statistics: None,
+            dirty_area: DirtyArea::default(),
         };
 
         let expected = [
@@ -431,6 +766,8 @@ mod tests {
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
             id: 0,
+            statistics: None,
+            dirty_area: DirtyArea::default(),
         };
 
         assert_eq!(&display.to_be_bytes(), &expected);
@@ -446,6 +783,8 @@ mod tests {
             size: Size::new(2, 1),
             pixels: expected.clone().into_boxed_slice(),
             id: 0,
+            statistics: None,
+            dirty_area: DirtyArea::default(),
         };
 
         assert_eq!(&display.to_be_bytes(), &[0x80, 0x00, 0x00, 0x01]);
@@ -460,6 +799,8 @@ mod tests {
             size: Size::new(2, 1),
             pixels: expected.clone().into_boxed_slice(),
             id: 0,
+            statistics: None,
+            dirty_area: DirtyArea::default(),
         };
 
         assert_eq!(
@@ -495,6 +836,226 @@ mod tests {
         assert_eq!(display.diff(&expected), Some(display));
     }
 
+    #[test]
+    fn fill_solid() {
+        let mut display = SimulatorDisplay::<Gray8>::new(Size::new(4, 3));
+
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(-1, 1), Size::new(3, 10)),
+                Gray8::new(1),
+            )
+            .unwrap();
+
+        assert_eq!(
+            display.to_be_bytes(),
+            [
+                0, 0, 0, 0, //
+                1, 1, 0, 0, //
+                1, 1, 0, 0, //
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_contiguous() {
+        let mut display = SimulatorDisplay::<Gray8>::new(Size::new(4, 3));
+
+        display
+            .fill_contiguous(
+                &Rectangle::new(Point::new(2, -1), Size::new(3, 3)),
+                (1..=9).map(Gray8::new),
+            )
+            .unwrap();
+
+        assert_eq!(
+            display.to_be_bytes(),
+            [
+                0, 0, 4, 5, //
+                0, 0, 7, 8, //
+                0, 0, 0, 0, //
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_contiguous_too_few_colors() {
+        let mut display = SimulatorDisplay::<Gray8>::new(Size::new(4, 3));
+        display.enable_statistics();
+
+        display
+            .fill_contiguous(
+                &Rectangle::new(Point::new(1, 0), Size::new(2, 3)),
+                (1..=3).map(Gray8::new),
+            )
+            .unwrap();
+
+        assert_eq!(
+            display.to_be_bytes(),
+            [
+                0, 1, 2, 0, //
+                0, 3, 0, 0, //
+                0, 0, 0, 0, //
+            ]
+        );
+        assert_eq!(display.statistics().unwrap().total_writes(), 3);
+    }
+
+    #[test]
+    fn clear() {
+        let mut display = SimulatorDisplay::<Gray8>::new(Size::new(3, 2));
+
+        display.clear(Gray8::new(5)).unwrap();
+
+        assert_eq!(display.to_be_bytes(), [5; 6]);
+    }
+
+    #[test]
+    fn dirty_area() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(10, 8));
+        assert_eq!(display.dirty_area(), None);
+
+        Pixel(Point::new(3, 2), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(
+            display.dirty_area(),
+            Some(Rectangle::new(Point::new(3, 2), Size::new(1, 1)))
+        );
+
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(-2, 5), Size::new(4, 10)),
+                BinaryColor::On,
+            )
+            .unwrap();
+        assert_eq!(
+            display.dirty_area(),
+            Some(Rectangle::with_corners(Point::new(0, 2), Point::new(3, 7)))
+        );
+
+        display.reset_dirty_area();
+        assert_eq!(display.dirty_area(), None);
+
+        // Drawing outside the display doesn't change the dirty area.
+        Pixel(Point::new(20, 20), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+        display
+            .fill_contiguous(
+                &Rectangle::new(Point::new(20, 0), Size::new(2, 2)),
+                core::iter::repeat(BinaryColor::On),
+            )
+            .unwrap();
+        assert_eq!(display.dirty_area(), None);
+
+        display.clear(BinaryColor::Off).unwrap();
+        assert_eq!(display.dirty_area(), Some(display.bounding_box()));
+    }
+
+    #[test]
+    fn window_dirty_area_with_multiple_windows() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(10, 8));
+        let mut window_a = 0;
+        let mut window_b = 0;
+
+        let pixel = |x, y| Rectangle::new(Point::new(x, y), Size::new(1, 1));
+
+        Pixel(Point::new(1, 1), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(
+            display.take_window_dirty_area(&mut window_a),
+            Some(pixel(1, 1))
+        );
+
+        Pixel(Point::new(5, 5), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+        assert_eq!(
+            display.take_window_dirty_area(&mut window_a),
+            Some(pixel(5, 5))
+        );
+        assert_eq!(display.take_window_dirty_area(&mut window_a), None);
+
+        // The second window still gets all changes, even though the first
+        // window has already seen them.
+        assert_eq!(
+            display.take_window_dirty_area(&mut window_b),
+            Some(Rectangle::with_corners(Point::new(1, 1), Point::new(5, 5)))
+        );
+        assert_eq!(display.take_window_dirty_area(&mut window_b), None);
+
+        // Windows that missed too many generations redraw the whole display.
+        let mut window_c = display.dirty_area.generation;
+        for x in 0..DIRTY_AREA_HISTORY_LEN as i32 + 1 {
+            Pixel(Point::new(x, 0), BinaryColor::On)
+                .draw(&mut display)
+                .unwrap();
+            assert_eq!(
+                display.take_window_dirty_area(&mut window_a),
+                Some(pixel(x, 0))
+            );
+        }
+        assert_eq!(
+            display.take_window_dirty_area(&mut window_c),
+            Some(display.bounding_box())
+        );
+    }
+
+    #[test]
+    fn statistics_disabled_by_default() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 6));
+        display.clear(BinaryColor::On).unwrap();
+
+        assert_eq!(display.statistics(), None);
+    }
+
+    #[test]
+    fn statistics() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 6));
+        display.enable_statistics();
+
+        display.clear(BinaryColor::Off).unwrap();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(2, 3), Size::new(10, 20)),
+                BinaryColor::On,
+            )
+            .unwrap();
+        display
+            .fill_contiguous(
+                &Rectangle::new(Point::new(-1, 0), Size::new(2, 1)),
+                [BinaryColor::On, BinaryColor::On],
+            )
+            .unwrap();
+        Pixel(Point::new(3, 5), BinaryColor::Off)
+            .draw(&mut display)
+            .unwrap();
+
+        let statistics = display.statistics().unwrap();
+        assert_eq!(statistics.clear_calls(), 1);
+        assert_eq!(statistics.fill_solid_calls(), 1);
+        assert_eq!(statistics.fill_contiguous_calls(), 1);
+        assert_eq!(statistics.draw_iter_calls(), 1);
+
+        assert_eq!(statistics.pixel_writes(Point::new(0, 0)), 2);
+        assert_eq!(statistics.pixel_writes(Point::new(1, 0)), 1);
+        assert_eq!(statistics.pixel_writes(Point::new(2, 3)), 2);
+        assert_eq!(statistics.pixel_writes(Point::new(3, 5)), 3);
+        assert_eq!(statistics.total_writes(), 24 + 6 + 1 + 1);
+        assert_eq!(statistics.max_writes(), 3);
+        assert_eq!(statistics.overdrawn_pixels(), 6 + 1);
+
+        let heatmap = statistics.to_heatmap_output_image(&OutputSettings::default());
+        assert_eq!(heatmap.size(), display.size());
+
+        display.reset_statistics();
+        let statistics = display.statistics().unwrap();
+        assert_eq!(statistics.clear_calls(), 0);
+        assert_eq!(statistics.total_writes(), 0);
+    }
+
     #[test]
     #[should_panic(expected = "both displays must have the same size (self: 4x6, other: 4x5)")]
     fn diff_wrong_size() {
@@ -503,4 +1064,27 @@ mod tests {
 
         assert_eq!(display.diff(&expected), None);
     }
+
+    #[test]
+    fn clone_is_equal() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash(display: &SimulatorDisplay<BinaryColor>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            display.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(4, 4));
+        Pixel(Point::new(1, 2), BinaryColor::On)
+            .draw(&mut display)
+            .unwrap();
+        let clone = display.clone();
+
+        assert_ne!(display.id, clone.id);
+        assert_eq!(display, clone);
+        assert_eq!(hash(&display), hash(&clone));
+        assert_eq!(display.cmp(&clone), CmpOrdering::Equal);
+        assert_eq!(display.partial_cmp(&clone), Some(CmpOrdering::Equal));
+    }
 }
@@ -0,0 +1,467 @@
+use embedded_graphics::{
+    geometry::AnchorPoint,
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+
+use crate::{display::bounding_box_union, output_settings::OutputSettings, SimulatorDisplay};
+
+/// Vertical distance between a display and its caption.
+const CAPTION_SPACING: u32 = 4;
+
+/// Returns the text object that is used to draw the caption of a display.
+///
+/// The caption is centered below the display area.
+pub(crate) fn caption_text<'a>(
+    caption: &'a str,
+    display_area: &Rectangle,
+    color: Rgb888,
+) -> Text<'a, MonoTextStyle<'static, Rgb888>> {
+    let position = Point::new(
+        display_area.center().x,
+        display_area.top_left.y + (display_area.size.height + CAPTION_SPACING) as i32,
+    );
+    let text_style = TextStyleBuilder::new()
+        .alignment(Alignment::Center)
+        .baseline(Baseline::Top)
+        .build();
+
+    Text::with_text_style(
+        caption,
+        position,
+        MonoTextStyle::new(&FONT_6X10, color),
+        text_style,
+    )
+}
+
+/// Returns the area that is covered by the caption of a display.
+pub(crate) fn caption_area(caption: &str, display_area: &Rectangle) -> Rectangle {
+    caption_text(caption, display_area, Rgb888::WHITE).bounding_box()
+}
+
+/// Layout of displays in a [`MultiWindow`](crate::MultiWindow) or [`DisplayComposition`](crate::DisplayComposition).
+///
+/// A display layout calculates the offsets of multiple displays based on their
+/// output size and the size of the window that is required to show all
+/// displays. The following layouts are supported:
+///
+/// - [`row`](Self::row): displays are placed next to each other
+/// - [`column`](Self::column): displays are placed below each other
+/// - [`grid`](Self::grid): displays are placed in a grid with a fixed number of columns
+/// - [`anchored`](Self::anchored): displays are placed at an anchor point in a fixed size window
+///
+/// Optionally a caption can be added below each display.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{
+///     DisplayComposition, DisplayLayout, OutputSettings, OutputSettingsBuilder,
+///     SimulatorDisplay,
+/// };
+///
+/// let main_display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+/// let status_display = SimulatorDisplay::<BinaryColor>::new(Size::new(64, 32));
+///
+/// let layout = DisplayLayout::row()
+///     .margin(10)
+///     .spacing(10)
+///     .display(&main_display, &OutputSettingsBuilder::new().scale(2).build())
+///     .caption("Main display")
+///     .display(&status_display, &OutputSettings::default())
+///     .caption("Status display");
+///
+/// // `MultiWindow::add_layout` can be used in the same way to show the layout in a window.
+/// let mut composition = DisplayComposition::new(layout.size());
+/// let handles = composition.add_layout(&layout);
+/// assert_eq!(handles.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayLayout {
+    kind: LayoutKind,
+    margin: u32,
+    spacing: u32,
+    items: Vec<LayoutItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LayoutKind {
+    Row,
+    Column,
+    Grid { columns: usize },
+    Anchored { size: Size },
+}
+
+/// Display in a layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LayoutItem {
+    pub(crate) display_id: usize,
+    pub(crate) display_size: Size,
+    pub(crate) output_settings: OutputSettings,
+    pub(crate) caption: Option<String>,
+    anchor: AnchorPoint,
+}
+
+impl LayoutItem {
+    /// Returns the area that is covered by the display and the caption.
+    ///
+    /// The area is relative to the top left corner of the display.
+    fn area(&self) -> Rectangle {
+        let display_area = Rectangle::new(
+            Point::zero(),
+            self.output_settings.output_size(self.display_size),
+        );
+
+        match &self.caption {
+            Some(caption) => {
+                bounding_box_union(&display_area, &caption_area(caption, &display_area))
+            }
+            None => display_area,
+        }
+    }
+}
+
+impl DisplayLayout {
+    fn new(kind: LayoutKind) -> Self {
+        Self {
+            kind,
+            margin: 0,
+            spacing: 0,
+            items: Vec::new(),
+        }
+    }
+
+    /// Creates a layout that places displays next to each other.
+    ///
+    /// The displays are aligned to the top edge of the row.
+    pub fn row() -> Self {
+        Self::new(LayoutKind::Row)
+    }
+
+    /// Creates a layout that places displays below each other.
+    ///
+    /// The displays are aligned to the left edge of the column.
+    pub fn column() -> Self {
+        Self::new(LayoutKind::Column)
+    }
+
+    /// Creates a layout that places displays in a grid.
+    ///
+    /// The displays are added row by row. The width of each column and the
+    /// height of each row is determined by the largest display in the column
+    /// or row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `columns` is `0`.
+    pub fn grid(columns: usize) -> Self {
+        assert!(columns > 0, "columns must be > 0");
+
+        Self::new(LayoutKind::Grid { columns })
+    }
+
+    /// Creates a layout that places displays at anchor points in a window with a fixed size.
+    ///
+    /// The anchor point of each display is set by [`anchor`](Self::anchor) and
+    /// defaults to the center of the window. The margin is applied to all edges
+    /// of the window.
+    pub fn anchored(window_size: Size) -> Self {
+        Self::new(LayoutKind::Anchored { size: window_size })
+    }
+
+    /// Sets the margin between the edges of the window and the displays.
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+
+        self
+    }
+
+    /// Sets the spacing between adjacent displays.
+    ///
+    /// The spacing isn't used by anchored layouts.
+    pub fn spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+
+        self
+    }
+
+    /// Adds a display to the layout.
+    pub fn display<C>(
+        mut self,
+        display: &SimulatorDisplay<C>,
+        output_settings: &OutputSettings,
+    ) -> Self {
+        self.items.push(LayoutItem {
+            display_id: display.id,
+            display_size: display.size(),
+            output_settings: *output_settings,
+            caption: None,
+            anchor: AnchorPoint::Center,
+        });
+
+        self
+    }
+
+    /// Sets the caption of the last added display.
+    ///
+    /// The caption is shown centered below the display.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no display was added to the layout.
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.last_item().caption = Some(String::from(caption));
+
+        self
+    }
+
+    /// Sets the anchor point of the last added display.
+    ///
+    /// The anchor point is only used by [`anchored`](Self::anchored) layouts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no display was added to the layout.
+    pub fn anchor(mut self, anchor: AnchorPoint) -> Self {
+        self.last_item().anchor = anchor;
+
+        self
+    }
+
+    fn last_item(&mut self) -> &mut LayoutItem {
+        self.items
+            .last_mut()
+            .expect("no display was added to the layout")
+    }
+
+    /// Returns the window size that is required to show all displays.
+    pub fn size(&self) -> Size {
+        let margins = Size::new_equal(self.margin * 2);
+
+        match self.kind {
+            LayoutKind::Anchored { size } => size,
+            LayoutKind::Row => {
+                let areas = self.item_areas();
+                let width = areas.iter().map(|area| area.size.width).sum::<u32>()
+                    + self.spacing_total(areas.len());
+                let height = areas.iter().map(|area| area.size.height).max();
+
+                Size::new(width, height.unwrap_or(0)) + margins
+            }
+            LayoutKind::Column => {
+                let areas = self.item_areas();
+                let width = areas.iter().map(|area| area.size.width).max();
+                let height = areas.iter().map(|area| area.size.height).sum::<u32>()
+                    + self.spacing_total(areas.len());
+
+                Size::new(width.unwrap_or(0), height) + margins
+            }
+            LayoutKind::Grid { columns } => {
+                let (column_widths, row_heights) = self.grid_cells(columns);
+                let width =
+                    column_widths.iter().sum::<u32>() + self.spacing_total(column_widths.len());
+                let height =
+                    row_heights.iter().sum::<u32>() + self.spacing_total(row_heights.len());
+
+                Size::new(width, height) + margins
+            }
+        }
+    }
+
+    /// Returns the offsets of the displays in the order they were added.
+    pub(crate) fn offsets(&self) -> Vec<Point> {
+        let areas = self.item_areas();
+        let margin = self.margin as i32;
+        let spacing = self.spacing as i32;
+
+        // Top left corners of the areas that contain the display and caption.
+        let positions: Vec<Point> = match self.kind {
+            LayoutKind::Row => areas
+                .iter()
+                .scan(margin, |x, area| {
+                    let position = Point::new(*x, margin);
+                    *x += area.size.width as i32 + spacing;
+                    Some(position)
+                })
+                .collect(),
+            LayoutKind::Column => areas
+                .iter()
+                .scan(margin, |y, area| {
+                    let position = Point::new(margin, *y);
+                    *y += area.size.height as i32 + spacing;
+                    Some(position)
+                })
+                .collect(),
+            LayoutKind::Grid { columns } => {
+                let (column_widths, row_heights) = self.grid_cells(columns);
+                let column_x = cell_positions(&column_widths, margin, spacing);
+                let row_y = cell_positions(&row_heights, margin, spacing);
+
+                (0..areas.len())
+                    .map(|index| Point::new(column_x[index % columns], row_y[index / columns]))
+                    .collect()
+            }
+            LayoutKind::Anchored { size } => {
+                let layout_area = Rectangle::new(Point::zero(), size).offset(-(self.margin as i32));
+
+                areas
+                    .iter()
+                    .zip(self.items.iter())
+                    .map(|(area, item)| layout_area.resized(area.size, item.anchor).top_left)
+                    .collect()
+            }
+        };
+
+        positions
+            .iter()
+            .zip(areas.iter())
+            .map(|(position, area)| *position - area.top_left)
+            .collect()
+    }
+
+    pub(crate) fn items(&self) -> &[LayoutItem] {
+        &self.items
+    }
+
+    fn item_areas(&self) -> Vec<Rectangle> {
+        self.items.iter().map(LayoutItem::area).collect()
+    }
+
+    /// Returns the column widths and row heights of a grid layout.
+    fn grid_cells(&self, columns: usize) -> (Vec<u32>, Vec<u32>) {
+        let areas = self.item_areas();
+
+        let mut column_widths = vec![0; columns.min(areas.len())];
+        let mut row_heights = vec![0; areas.len().div_ceil(columns)];
+
+        for (index, area) in areas.iter().enumerate() {
+            let width = &mut column_widths[index % columns];
+            *width = (*width).max(area.size.width);
+
+            let height = &mut row_heights[index / columns];
+            *height = (*height).max(area.size.height);
+        }
+
+        (column_widths, row_heights)
+    }
+
+    /// Returns the total spacing between `count` adjacent elements.
+    fn spacing_total(&self, count: usize) -> u32 {
+        self.spacing * count.saturating_sub(1) as u32
+    }
+}
+
+/// Returns the start positions of consecutive cells.
+fn cell_positions(sizes: &[u32], margin: i32, spacing: i32) -> Vec<i32> {
+    sizes
+        .iter()
+        .scan(margin, |position, size| {
+            let start = *position;
+            *position += *size as i32 + spacing;
+            Some(start)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use crate::OutputSettingsBuilder;
+
+    fn display(width: u32, height: u32) -> SimulatorDisplay<BinaryColor> {
+        SimulatorDisplay::new(Size::new(width, height))
+    }
+
+    #[test]
+    fn row() {
+        let scaled = OutputSettingsBuilder::new().scale(2).build();
+        let layout = DisplayLayout::row()
+            .margin(5)
+            .spacing(10)
+            .display(&display(20, 10), &OutputSettings::default())
+            .display(&display(10, 15), &scaled);
+
+        assert_eq!(layout.size(), Size::new(5 + 20 + 10 + 20 + 5, 5 + 30 + 5));
+        assert_eq!(layout.offsets(), [Point::new(5, 5), Point::new(35, 5)]);
+    }
+
+    #[test]
+    fn column() {
+        let layout = DisplayLayout::column()
+            .spacing(2)
+            .display(&display(20, 10), &OutputSettings::default())
+            .display(&display(10, 15), &OutputSettings::default());
+
+        assert_eq!(layout.size(), Size::new(20, 10 + 2 + 15));
+        assert_eq!(layout.offsets(), [Point::new(0, 0), Point::new(0, 12)]);
+    }
+
+    #[test]
+    fn grid() {
+        let settings = OutputSettings::default();
+        let layout = DisplayLayout::grid(2)
+            .margin(1)
+            .spacing(3)
+            .display(&display(10, 10), &settings)
+            .display(&display(20, 5), &settings)
+            .display(&display(5, 20), &settings);
+
+        assert_eq!(
+            layout.size(),
+            Size::new(1 + 10 + 3 + 20 + 1, 1 + 10 + 3 + 20 + 1)
+        );
+        assert_eq!(
+            layout.offsets(),
+            [Point::new(1, 1), Point::new(14, 1), Point::new(1, 14)]
+        );
+    }
+
+    #[test]
+    fn anchored() {
+        let settings = OutputSettings::default();
+        let layout = DisplayLayout::anchored(Size::new(100, 50))
+            .margin(10)
+            .display(&display(20, 10), &settings)
+            .anchor(AnchorPoint::TopLeft)
+            .display(&display(20, 10), &settings)
+            .anchor(AnchorPoint::BottomRight)
+            .display(&display(20, 10), &settings);
+
+        assert_eq!(layout.size(), Size::new(100, 50));
+        assert_eq!(
+            layout.offsets(),
+            [Point::new(10, 10), Point::new(70, 30), Point::new(40, 20)]
+        );
+    }
+
+    #[test]
+    fn captions() {
+        let settings = OutputSettings::default();
+        let layout = DisplayLayout::row()
+            .display(&display(10, 10), &settings)
+            .caption("Wide caption")
+            .display(&display(20, 10), &settings);
+
+        // The caption is 12 * 6 pixels wide and centered below the display.
+        let caption_width = 12 * 6;
+        let caption_height = 10;
+        assert_eq!(
+            layout.size(),
+            Size::new(caption_width + 20, 10 + CAPTION_SPACING + caption_height)
+        );
+        assert_eq!(
+            layout.offsets(),
+            [
+                Point::new((caption_width as i32 - 10) / 2, 0),
+                Point::new(caption_width as i32, 0)
+            ]
+        );
+    }
+}
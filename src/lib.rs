@@ -124,6 +124,15 @@
 //! `EG_SIMULATOR_CHECK` assumes that the reference image was created using the same
 //! `OutputSetting`s, while `EG_SIMULATOR_CHECK_RAW` assumes an unstyled reference image.
 //!
+//! Animations can be made reproducible by using the simulated time returned by [`Window::now`]
+//! to drive them. If the `EG_SIMULATOR_FAST` environment variable is set to `1`, the window
+//! doesn't sleep to limit the frame rate and instead advances the simulated time by exactly one
+//! frame per update:
+//!
+//! ```bash
+//! EG_SIMULATOR_FAST=1 cargo test
+//! ```
+//!
 //! # Usage without SDL2
 //!
 //! When the simulator is used in headless/CI environments that don't require showing a window, SDL2
@@ -152,10 +161,19 @@
     rustdoc::private_intra_doc_links
 )]
 
+mod bezel;
+#[cfg(feature = "embedded-hal")]
+mod button_pins;
+mod composition;
 mod display;
+mod layout;
 mod output_image;
 mod output_settings;
+#[cfg(feature = "with-sdl")]
+mod rotary_encoder;
+mod statistics;
 mod theme;
+mod widgets;
 mod window;
 
 /// Re-exported types from sdl2 crate.
@@ -165,18 +183,33 @@ mod window;
 #[cfg(feature = "with-sdl")]
 pub mod sdl2 {
     pub use sdl2::{
+        controller::{Axis, Button},
         keyboard::{Keycode, Mod},
         mouse::{MouseButton, MouseWheelDirection},
     };
 }
 
 pub use crate::{
+    bezel::Bezel,
+    composition::{DisplayComposition, DisplayHandle},
     display::SimulatorDisplay,
+    layout::DisplayLayout,
     output_image::OutputImage,
     output_settings::{OutputSettings, OutputSettingsBuilder},
+    statistics::DrawStatistics,
     theme::BinaryColorTheme,
-    window::Window,
+    widgets::{Led, VirtualButton},
+    window::{FrameStatistics, Window},
 };
 
+#[cfg(feature = "embedded-hal")]
+pub use button_pins::{ButtonPin, ButtonPins};
+
+#[cfg(feature = "with-sdl")]
+pub use rotary_encoder::RotaryEncoder;
+
 #[cfg(feature = "with-sdl")]
-pub use window::{MultiWindow, SimulatorEvent, SimulatorEventsIter};
+pub use window::{
+    DisplayEvent, KeyCombination, KeyboardState, MouseButtons, MouseState, MultiWindow,
+    SimulatorEvent, SimulatorEventsIter, MOUSE_FINGER_ID, PINCH_FINGER_ID,
+};
@@ -1,4 +1,4 @@
-use std::{convert::TryFrom, marker::PhantomData, path::Path};
+use std::{convert::TryFrom, fs::File, io::BufReader, marker::PhantomData, ops::Range, path::Path};
 
 use base64::Engine;
 use embedded_graphics::{
@@ -57,40 +57,358 @@ where
     ) where
         DisplayC: PixelColor + Into<Rgb888>,
     {
-        let display_area = Rectangle::new(position, display.output_size(output_settings));
-        self.fill_solid(
-            &display_area,
-            output_settings.theme.convert(Rgb888::BLACK).into(),
-        )
-        .unwrap();
+        // Pixel spacing isn't applied to displays with a scale of `1`, but the
+        // background is still drawn using the full output size.
+        if output_settings.scale == 1 && output_settings.pixel_spacing > 0 {
+            let display_area = Rectangle::new(position, display.output_size(output_settings));
+            self.fill_solid(
+                &display_area,
+                output_settings.theme.convert(Rgb888::BLACK).into(),
+            )
+            .unwrap();
+        }
 
-        if output_settings.scale == 1 {
-            display
-                .bounding_box()
-                .points()
-                .map(|p| {
-                    let raw_color = display.get_pixel(p).into();
-                    let themed_color = output_settings.theme.convert(raw_color);
-                    let output_color = C::from(themed_color);
+        self.draw_display_area(display, position, output_settings, &display.bounding_box());
+    }
 
-                    Pixel(p + position, output_color)
-                })
-                .draw(self)
-                .unwrap();
-        } else {
-            let pixel_pitch = (output_settings.scale + output_settings.pixel_spacing) as i32;
-            let pixel_size = Size::new(output_settings.scale, output_settings.scale);
+    /// Draws a part of a display using the given position and output setting.
+    ///
+    /// `area` is specified in display coordinates. Only the output pixels that
+    /// correspond to display pixels inside `area` are modified. The return
+    /// value is the area of the output image that was modified.
+    pub(crate) fn draw_display_area<DisplayC>(
+        &mut self,
+        display: &SimulatorDisplay<DisplayC>,
+        position: Point,
+        output_settings: &OutputSettings,
+        area: &Rectangle,
+    ) -> Rectangle
+    where
+        DisplayC: PixelColor + Into<Rgb888>,
+    {
+        let area = area.intersection(&display.bounding_box());
+        if area.is_zero_sized() {
+            return Rectangle::zero();
+        }
 
-            for p in display.bounding_box().points() {
-                let raw_color = display.get_pixel(p).into();
-                let themed_color = output_settings.theme.convert(raw_color);
-                let output_color = C::from(themed_color);
+        let output_settings = &rendered_output_settings(output_settings);
+        let pitch = output_settings.pixel_pitch();
+        let output_area = Rectangle::new(
+            position + area.top_left * pitch as i32,
+            output_settings.output_size(area.size),
+        );
+        let visible_area = output_area.intersection(&self.bounding_box());
+        if visible_area.is_zero_sized() {
+            return Rectangle::zero();
+        }
 
-                self.fill_solid(
-                    &Rectangle::new(p * pixel_pitch + position, pixel_size),
-                    output_color,
-                )
-                .unwrap();
+        let background_color = C::from(output_settings.theme.convert(Rgb888::BLACK));
+
+        // The gaps between pixels aren't covered by the rendered rows and need
+        // to be filled with the background color first.
+        if output_settings.pixel_spacing > 0 {
+            self.fill_solid(&output_area, background_color).unwrap();
+        }
+
+        let mut renderer = DisplayRenderer {
+            colors: Vec::new(),
+            background: background_color.to_image_bytes(),
+            display_rows: 0..0,
+            display_columns: 0..0,
+            scale: output_settings.scale as usize,
+            pitch: pitch as usize,
+            position,
+            visible_area,
+        };
+        renderer.convert_colors::<C, _>(display, output_settings);
+
+        let bytes_per_row = self.size.width as usize * C::BYTES_PER_PIXEL;
+        let display_rows = renderer.display_rows.clone();
+
+        // Rendering small areas is faster than spawning threads.
+        let visible_pixels = visible_area.size.width as usize * visible_area.size.height as usize;
+        let threads = output_settings
+            .render_threads
+            .min(visible_pixels / MIN_PIXELS_PER_RENDER_THREAD)
+            .clamp(1, display_rows.len());
+
+        if threads == 1 {
+            let rows = visible_area.rows();
+            let band_start = rows.start as usize * bytes_per_row;
+            let band_end = rows.end as usize * bytes_per_row;
+
+            renderer.render_band(
+                &mut self.data[band_start..band_end],
+                rows.start,
+                display_rows,
+                bytes_per_row,
+                &mut self.row_buffer,
+            );
+
+            return visible_area;
+        }
+
+        // Split the visible part of the output image into horizontal bands,
+        // which are rendered in parallel.
+        let rows_per_thread = display_rows.len().div_ceil(threads);
+        let first_output_row = visible_area.rows().start;
+
+        let mut remaining = &mut self.data[first_output_row as usize * bytes_per_row..];
+        let mut bands = Vec::with_capacity(threads);
+        let mut band_start = first_output_row;
+
+        for band_display_start in display_rows.clone().step_by(rows_per_thread) {
+            let band_display_rows =
+                band_display_start..(band_display_start + rows_per_thread).min(display_rows.end);
+
+            let band_end = if band_display_rows.end == display_rows.end {
+                visible_area.rows().end
+            } else {
+                renderer.output_row(band_display_rows.end)
+            };
+
+            let (band, rest) = std::mem::take(&mut remaining)
+                .split_at_mut((band_end - band_start) as usize * bytes_per_row);
+            remaining = rest;
+
+            bands.push((band, band_start, band_display_rows));
+            band_start = band_end;
+        }
+
+        std::thread::scope(|scope| {
+            for (band, band_start, band_display_rows) in bands {
+                let renderer = &renderer;
+
+                scope.spawn(move || {
+                    renderer.render_band(
+                        band,
+                        band_start,
+                        band_display_rows,
+                        bytes_per_row,
+                        &mut Vec::new(),
+                    );
+                });
+            }
+        });
+
+        visible_area
+    }
+
+    /// Copies a part of another output image into this image.
+    ///
+    /// The pixels inside `source_area` are copied to the same location offset
+    /// by `position`. Pixels outside of this image are ignored. The return
+    /// value is the area of this image that was modified.
+    pub(crate) fn copy_area(
+        &mut self,
+        source: &Self,
+        source_area: &Rectangle,
+        position: Point,
+    ) -> Rectangle {
+        let source_area = source_area.intersection(&source.bounding_box());
+        let target_area = Rectangle::new(source_area.top_left + position, source_area.size)
+            .intersection(&self.bounding_box());
+        if target_area.is_zero_sized() {
+            return Rectangle::zero();
+        }
+
+        let source_bytes_per_row = source.size.width as usize * C::BYTES_PER_PIXEL;
+        let target_bytes_per_row = self.size.width as usize * C::BYTES_PER_PIXEL;
+        let row_len = target_area.size.width as usize * C::BYTES_PER_PIXEL;
+
+        for y in target_area.rows() {
+            let source_point = Point::new(target_area.top_left.x, y) - position;
+            let source_start = source_point.y as usize * source_bytes_per_row
+                + source_point.x as usize * C::BYTES_PER_PIXEL;
+            let target_start = y as usize * target_bytes_per_row
+                + target_area.top_left.x as usize * C::BYTES_PER_PIXEL;
+
+            self.data[target_start..target_start + row_len]
+                .copy_from_slice(&source.data[source_start..source_start + row_len]);
+        }
+
+        target_area
+    }
+}
+
+/// Minimum number of output pixels per render thread.
+const MIN_PIXELS_PER_RENDER_THREAD: usize = 1 << 16;
+
+/// Returns the output settings that are used to render display pixels.
+///
+/// Pixel spacing is ignored for displays with a scale of `1`.
+fn rendered_output_settings(output_settings: &OutputSettings) -> OutputSettings {
+    if output_settings.scale == 1 {
+        OutputSettings {
+            pixel_spacing: 0,
+            ..*output_settings
+        }
+    } else {
+        *output_settings
+    }
+}
+
+/// Maximum number of colors that are cached by a `ThemeCache`.
+const THEME_CACHE_CAPACITY: usize = 16;
+
+/// Cache for themed color conversions.
+///
+/// Converting a display color into an output color requires a conversion into
+/// `Rgb888` and the application of the theme. Most simulated displays only
+/// use a small palette, so the converted colors are cached. The cache is
+/// disabled if the display contains more than `THEME_CACHE_CAPACITY` colors,
+/// because the linear search would be slower than the conversion.
+struct ThemeCache<DisplayC, C: OutputImageColor> {
+    output_settings: OutputSettings,
+    entries: Vec<(DisplayC, C::Bytes)>,
+    enabled: bool,
+}
+
+impl<DisplayC, C> ThemeCache<DisplayC, C>
+where
+    DisplayC: PixelColor + Into<Rgb888>,
+    C: OutputImageColor + From<Rgb888>,
+{
+    fn new(output_settings: &OutputSettings) -> Self {
+        Self {
+            output_settings: *output_settings,
+            entries: Vec::with_capacity(THEME_CACHE_CAPACITY),
+            enabled: true,
+        }
+    }
+
+    fn convert(&mut self, color: DisplayC) -> C::Bytes {
+        if self.enabled {
+            if let Some((_, bytes)) = self.entries.iter().find(|(c, _)| *c == color) {
+                return *bytes;
+            }
+        }
+
+        let themed_color = self.output_settings.theme.convert(color.into());
+        let bytes = C::from(themed_color).to_image_bytes();
+
+        if self.enabled {
+            if self.entries.len() < THEME_CACHE_CAPACITY {
+                self.entries.push((color, bytes));
+            } else {
+                self.enabled = false;
+                self.entries.clear();
+            }
+        }
+
+        bytes
+    }
+}
+
+/// Renders the themed colors of a display into output image rows.
+struct DisplayRenderer<B> {
+    /// Themed colors of the rendered display pixels in output image format.
+    colors: Vec<u8>,
+    /// Background color in output image format.
+    background: B,
+    /// Display rows that are at least partially visible.
+    display_rows: Range<usize>,
+    /// Display columns that are at least partially visible.
+    display_columns: Range<usize>,
+    scale: usize,
+    pitch: usize,
+    /// Position of the display in the output image.
+    position: Point,
+    /// Part of the output image that is rendered.
+    visible_area: Rectangle,
+}
+
+impl<B: AsRef<[u8]>> DisplayRenderer<B> {
+    /// Converts the colors of the visible display pixels.
+    fn convert_colors<C, DisplayC>(
+        &mut self,
+        display: &SimulatorDisplay<DisplayC>,
+        output_settings: &OutputSettings,
+    ) where
+        C: OutputImageColor + From<Rgb888>,
+        DisplayC: PixelColor + Into<Rgb888>,
+    {
+        let rows = self.visible_area.rows();
+        let columns = self.visible_area.columns();
+
+        self.display_rows = (rows.start - self.position.y) as usize / self.pitch
+            ..(rows.end - 1 - self.position.y) as usize / self.pitch + 1;
+        self.display_columns = (columns.start - self.position.x) as usize / self.pitch
+            ..(columns.end - 1 - self.position.x) as usize / self.pitch + 1;
+
+        let display_width = display.size().width as usize;
+        let mut theme_cache = ThemeCache::<_, C>::new(output_settings);
+
+        self.colors.clear();
+        for y in self.display_rows.clone() {
+            let row_start = y * display_width;
+            let row = &display.pixels
+                [row_start + self.display_columns.start..row_start + self.display_columns.end];
+
+            for pixel in row {
+                self.colors
+                    .extend_from_slice(theme_cache.convert(*pixel).as_ref());
+            }
+        }
+    }
+
+    /// Returns the first output row of a display row.
+    fn output_row(&self, display_row: usize) -> i32 {
+        self.position.y + (display_row * self.pitch) as i32
+    }
+
+    /// Builds the scaled visible part of a display row.
+    fn build_row(&self, display_row: usize, row: &mut Vec<u8>) {
+        let background = self.background.as_ref();
+        let bytes_per_pixel = background.len();
+        let bytes_per_row = self.display_columns.len() * bytes_per_pixel;
+
+        let start = (display_row - self.display_rows.start) * bytes_per_row;
+        let colors = &self.colors[start..start + bytes_per_row];
+
+        row.clear();
+
+        for color in colors.chunks_exact(bytes_per_pixel) {
+            for _ in 0..self.scale {
+                row.extend_from_slice(color);
+            }
+            for _ in self.scale..self.pitch {
+                row.extend_from_slice(background);
+            }
+        }
+
+        // Remove the parts of the row that are outside the visible area.
+        let first_column = (self.visible_area.top_left.x - self.position.x) as usize;
+        let offset = first_column - self.display_columns.start * self.pitch;
+        row.truncate((offset + self.visible_area.size.width as usize) * bytes_per_pixel);
+        row.drain(..offset * bytes_per_pixel);
+    }
+
+    /// Renders display rows into a band of output image rows.
+    ///
+    /// `band` contains the image data starting at output row `band_start`.
+    fn render_band(
+        &self,
+        band: &mut [u8],
+        band_start: i32,
+        display_rows: Range<usize>,
+        bytes_per_row: usize,
+        row: &mut Vec<u8>,
+    ) {
+        let bytes_per_pixel = self.background.as_ref().len();
+        let x_start = self.visible_area.top_left.x as usize * bytes_per_pixel;
+        let visible_rows = self.visible_area.rows();
+
+        for display_row in display_rows {
+            self.build_row(display_row, row);
+
+            let output_row = self.output_row(display_row);
+            let output_rows = output_row.max(visible_rows.start)
+                ..(output_row + self.scale as i32).min(visible_rows.end);
+
+            for y in output_rows {
+                let start = (y - band_start) as usize * bytes_per_row + x_start;
+                band[start..start + row.len()].copy_from_slice(row);
             }
         }
     }
@@ -133,6 +451,19 @@ impl<C: OutputImageColor> OutputImage<C> {
     }
 }
 
+impl OutputImage<Rgb888> {
+    /// Loads a PNG file.
+    pub fn load_png<P: AsRef<Path>>(path: P) -> image::ImageResult<Self> {
+        let png_file = BufReader::new(File::open(path)?);
+        let image = image::load(png_file, image::ImageFormat::Png)?.to_rgb8();
+
+        let mut output_image = Self::new(Size::new(image.width(), image.height()));
+        output_image.data = image.into_raw().into_boxed_slice();
+
+        Ok(output_image)
+    }
+}
+
 impl DrawTarget for OutputImage<Rgb888> {
     type Color = Rgb888;
     type Error = ();
@@ -247,26 +578,208 @@ impl<C> OriginDimensions for OutputImage<C> {
 
 pub trait OutputImageColor {
     type ImageColor: image::Pixel<Subpixel = u8> + 'static;
+    type Bytes: AsRef<[u8]> + Copy + Send + Sync;
     const IMAGE_COLOR_TYPE: image::ColorType;
     const BYTES_PER_PIXEL: usize;
+
+    /// Converts the color into the representation used in the image data.
+    fn to_image_bytes(self) -> Self::Bytes;
 }
 
 impl OutputImageColor for Gray8 {
     type ImageColor = Luma<u8>;
+    type Bytes = [u8; 1];
     const IMAGE_COLOR_TYPE: image::ColorType = image::ColorType::L8;
     const BYTES_PER_PIXEL: usize = 1;
+
+    fn to_image_bytes(self) -> Self::Bytes {
+        [self.into_storage()]
+    }
 }
 
 impl OutputImageColor for Rgb888 {
     type ImageColor = Rgb<u8>;
+    type Bytes = [u8; 3];
     const IMAGE_COLOR_TYPE: image::ColorType = image::ColorType::Rgb8;
     const BYTES_PER_PIXEL: usize = 3;
+
+    fn to_image_bytes(self) -> Self::Bytes {
+        self.to_be_bytes()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use embedded_graphics::pixelcolor::{BinaryColor, Rgb565};
+
+    use crate::{BinaryColorTheme, OutputSettingsBuilder};
+
+    /// Original pixel by pixel implementation of `draw_display`, which is used as a reference.
+    fn draw_display_reference<C, DisplayC>(
+        image: &mut OutputImage<C>,
+        display: &SimulatorDisplay<DisplayC>,
+        position: Point,
+        output_settings: &OutputSettings,
+    ) where
+        C: PixelColor + OutputImageColor + From<Rgb888>,
+        OutputImage<C>: DrawTarget<Color = C, Error = ()>,
+        DisplayC: PixelColor + Into<Rgb888>,
+    {
+        let display_area = Rectangle::new(position, display.output_size(output_settings));
+        image
+            .fill_solid(
+                &display_area,
+                output_settings.theme.convert(Rgb888::BLACK).into(),
+            )
+            .unwrap();
+
+        if output_settings.scale == 1 {
+            display
+                .bounding_box()
+                .points()
+                .map(|p| {
+                    let raw_color = display.get_pixel(p).into();
+                    let themed_color = output_settings.theme.convert(raw_color);
+                    let output_color = C::from(themed_color);
+
+                    Pixel(p + position, output_color)
+                })
+                .draw(image)
+                .unwrap();
+        } else {
+            let pixel_pitch = (output_settings.scale + output_settings.pixel_spacing) as i32;
+            let pixel_size = Size::new(output_settings.scale, output_settings.scale);
+
+            for p in display.bounding_box().points() {
+                let raw_color = display.get_pixel(p).into();
+                let themed_color = output_settings.theme.convert(raw_color);
+                let output_color = C::from(themed_color);
+
+                image
+                    .fill_solid(
+                        &Rectangle::new(p * pixel_pitch + position, pixel_size),
+                        output_color,
+                    )
+                    .unwrap();
+            }
+        }
+    }
+
+    fn assert_draw_display_matches_reference<C, DisplayC>(display: &SimulatorDisplay<DisplayC>)
+    where
+        C: PixelColor + OutputImageColor + From<Rgb888> + core::fmt::Debug,
+        OutputImage<C>: DrawTarget<Color = C, Error = ()>,
+        DisplayC: PixelColor + Into<Rgb888>,
+    {
+        let image_size = Size::new(50, 40);
+
+        for scale in 1..=3 {
+            for pixel_spacing in 0..=2 {
+                for render_threads in [1, 3] {
+                    for theme in [BinaryColorTheme::Default, BinaryColorTheme::LcdGreen] {
+                        let output_settings = OutputSettingsBuilder::new()
+                            .theme(theme)
+                            .scale(scale)
+                            .pixel_spacing(pixel_spacing)
+                            .render_threads(render_threads)
+                            .build();
+
+                        for position in [
+                            Point::new(0, 0),
+                            Point::new(3, 5),
+                            Point::new(-7, -4),
+                            Point::new(30, 25),
+                            Point::new(-100, 0),
+                        ] {
+                            let mut expected = OutputImage::<C>::new(image_size);
+                            expected
+                                .fill_solid(&expected.bounding_box(), C::from(Rgb888::new(1, 2, 3)))
+                                .unwrap();
+                            let mut image = expected.clone();
+
+                            draw_display_reference(
+                                &mut expected,
+                                display,
+                                position,
+                                &output_settings,
+                            );
+                            image.draw_display(display, position, &output_settings);
+
+                            assert_eq!(
+                                image.data, expected.data,
+                                "{output_settings:?}, position: {position:?}",
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn draw_display_binary_color() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(9, 7));
+        display
+            .draw_iter(
+                display
+                    .bounding_box()
+                    .points()
+                    .map(|p| Pixel(p, BinaryColor::from((p.x + 2 * p.y) % 3 == 0))),
+            )
+            .unwrap();
+
+        assert_draw_display_matches_reference::<Rgb888, _>(&display);
+        assert_draw_display_matches_reference::<Gray8, _>(&display);
+    }
+
+    #[test]
+    fn draw_display_rgb565() {
+        let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(11, 8));
+        display
+            .draw_iter(display.bounding_box().points().map(|p| {
+                Pixel(
+                    p,
+                    Rgb565::new((p.x * 3) as u8, (p.y * 7) as u8, (p.x * p.y) as u8),
+                )
+            }))
+            .unwrap();
+
+        assert_draw_display_matches_reference::<Rgb888, _>(&display);
+        assert_draw_display_matches_reference::<Gray8, _>(&display);
+    }
+
+    #[test]
+    fn draw_display_multiple_threads() {
+        let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(160, 160));
+        display
+            .draw_iter(display.bounding_box().points().map(|p| {
+                Pixel(
+                    p,
+                    Rgb565::new((p.x * 3) as u8, (p.y * 7) as u8, (p.x * p.y) as u8),
+                )
+            }))
+            .unwrap();
+
+        let output_settings = OutputSettingsBuilder::new()
+            .scale(3)
+            .pixel_spacing(1)
+            .render_threads(3)
+            .build();
+        let image_size = display.output_size(&output_settings);
+
+        for position in [Point::zero(), Point::new(-5, 7)] {
+            let mut expected = OutputImage::<Rgb888>::new(image_size);
+            let mut image = expected.clone();
+
+            draw_display_reference(&mut expected, &display, position, &output_settings);
+            image.draw_display(&display, position, &output_settings);
+
+            assert_eq!(image.data, expected.data, "position: {position:?}");
+        }
+    }
+
     #[test]
     fn rgb888_default_data() {
         let image = OutputImage::<Rgb888>::new(Size::new(6, 5));
@@ -328,6 +841,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn copy_area() {
+        let mut source = OutputImage::<Rgb888>::new(Size::new(4, 4));
+        source
+            .fill_solid(
+                &Rectangle::new(Point::new(1, 1), Size::new(2, 2)),
+                Rgb888::RED,
+            )
+            .unwrap();
+
+        let mut target = OutputImage::<Rgb888>::new(Size::new(4, 4));
+        let area = target.copy_area(&source, &source.bounding_box(), Point::new(2, 1));
+        assert_eq!(area, Rectangle::new(Point::new(2, 1), Size::new(2, 3)));
+
+        let mut expected = OutputImage::<Rgb888>::new(Size::new(4, 4));
+        expected
+            .fill_solid(
+                &Rectangle::new(Point::new(3, 2), Size::new(1, 2)),
+                Rgb888::RED,
+            )
+            .unwrap();
+        assert_eq!(target.data, expected.data);
+    }
+
     #[test]
     fn gray8_default_data() {
         let image = OutputImage::<Gray8>::new(Size::new(6, 5));
@@ -388,4 +925,20 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn rgb888_png_round_trip() {
+        let mut image = OutputImage::<Rgb888>::new(Size::new(3, 2));
+        Pixel(Point::new(1, 0), Rgb888::new(10, 20, 30))
+            .draw(&mut image)
+            .unwrap();
+
+        let path = std::env::temp_dir().join("eg-simulator-rgb888-png-round-trip.png");
+        image.save_png(&path).unwrap();
+        let loaded = OutputImage::<Rgb888>::load_png(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.size(), image.size());
+        assert_eq!(loaded.data, image.data);
+    }
 }
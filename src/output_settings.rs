@@ -10,19 +10,31 @@ pub struct OutputSettings {
     pub pixel_spacing: u32,
     /// Binary color theme.
     pub theme: BinaryColorTheme,
+    /// Number of threads used to render a display.
+    ///
+    /// See [`OutputSettingsBuilder::render_threads`] for more details. A value
+    /// of `0` is treated like `1`.
+    pub render_threads: usize,
 }
 
-#[cfg(feature = "with-sdl")]
 impl OutputSettings {
     /// Translates a output coordinate to the corresponding display coordinate.
     pub(crate) const fn output_to_display(&self, output_point: Point) -> Point {
         let pitch = self.pixel_pitch() as i32;
-        Point::new(output_point.x / pitch, output_point.y / pitch)
+        Point::new(
+            output_point.x.div_euclid(pitch),
+            output_point.y.div_euclid(pitch),
+        )
     }
 
     pub(crate) const fn pixel_pitch(&self) -> u32 {
         self.scale + self.pixel_spacing
     }
+
+    /// Calculates the output size of an area of display pixels.
+    pub(crate) fn output_size(&self, size: Size) -> Size {
+        size * self.scale + size.saturating_sub(Size::new_equal(1)) * self.pixel_spacing
+    }
 }
 
 impl Default for OutputSettings {
@@ -37,6 +49,7 @@ pub struct OutputSettingsBuilder {
     scale: Option<u32>,
     pixel_spacing: Option<u32>,
     theme: BinaryColorTheme,
+    render_threads: Option<usize>,
 }
 
 impl OutputSettingsBuilder {
@@ -94,12 +107,65 @@ impl OutputSettingsBuilder {
         self
     }
 
+    /// Sets the number of threads used to render a display.
+    ///
+    /// Rendering large displays with a high scale can be CPU intensive. Setting
+    /// this to a value greater than `1` splits the display into horizontal
+    /// bands which are rendered in parallel. Small displays are always
+    /// rendered by a single thread, because starting the threads would take
+    /// longer than rendering the display. The default value is `1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of threads is set to `0`.
+    pub fn render_threads(mut self, render_threads: usize) -> Self {
+        assert!(render_threads > 0, "render_threads must be > 0");
+
+        self.render_threads = Some(render_threads);
+
+        self
+    }
+
     /// Builds the output settings.
     pub fn build(self) -> OutputSettings {
         OutputSettings {
             scale: self.scale.unwrap_or(1),
             pixel_spacing: self.pixel_spacing.unwrap_or(0),
             theme: self.theme,
+            render_threads: self.render_threads.unwrap_or(1),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_to_display() {
+        let output_settings = OutputSettingsBuilder::new()
+            .scale(3)
+            .pixel_spacing(1)
+            .build();
+
+        assert_eq!(
+            output_settings.output_to_display(Point::new(0, 3)),
+            Point::new(0, 0)
+        );
+        assert_eq!(
+            output_settings.output_to_display(Point::new(4, 9)),
+            Point::new(1, 2)
+        );
+
+        // Positions to the left of or above the display must not be mapped to
+        // the first column or row.
+        assert_eq!(
+            output_settings.output_to_display(Point::new(-1, -4)),
+            Point::new(-1, -1)
+        );
+        assert_eq!(
+            output_settings.output_to_display(Point::new(-5, 0)),
+            Point::new(-2, 0)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{
+    sdl2::{Keycode, MouseButton, MouseWheelDirection},
+    SimulatorEvent,
+};
+
+/// Number of quadrature transitions per detent.
+const TRANSITIONS_PER_DETENT: i32 = 4;
+
+/// Simulated rotary encoder with push button.
+///
+/// The encoder is turned by the mouse wheel and, optionally, by two keyboard
+/// keys. Scrolling up or pressing the clockwise key turns the encoder one
+/// detent clockwise, which increments the [`position`](Self::position). The
+/// push button is bound to the middle mouse button and an optional key.
+///
+/// Besides the high level position counter the encoder also simulates the
+/// quadrature signals of the A and B pins, which can be used to test low level
+/// decoder code. Each detent consists of four transitions of the A and B
+/// signals. Because a single event can turn the encoder by multiple detents
+/// the transitions aren't applied immediately, but need to be applied one by
+/// one by calling [`step`](Self::step):
+///
+/// ```rust,no_run
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+/// use embedded_graphics_simulator::{
+///     sdl2::Keycode, OutputSettings, RotaryEncoder, SimulatorDisplay, SimulatorEvent, Window,
+/// };
+///
+/// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+/// let mut window = Window::new("Rotary encoder", &OutputSettings::default());
+///
+/// let mut encoder = RotaryEncoder::new().with_keys(Keycode::Left, Keycode::Right);
+///
+/// 'running: loop {
+///     window.update(&display);
+///
+///     for event in window.events() {
+///         encoder.handle_event(&event);
+///
+///         if event == SimulatorEvent::Quit {
+///             break 'running;
+///         }
+///     }
+///
+///     // high level API
+///     let position = encoder.position();
+///
+///     // low level API
+///     while encoder.step() {
+///         let (a, b) = (encoder.pin_a(), encoder.pin_b());
+///         // feed a and b into the decoder
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotaryEncoder {
+    position: i32,
+    pressed: bool,
+    /// Current quadrature phase in the range `0..4`.
+    phase: i32,
+    /// Quadrature transitions that haven't been applied by `step` yet.
+    pending_transitions: i32,
+    keys: Option<(Keycode, Keycode)>,
+    button_key: Option<Keycode>,
+}
+
+impl RotaryEncoder {
+    /// Creates a new rotary encoder.
+    ///
+    /// The encoder is controlled by the mouse wheel and the middle mouse
+    /// button.
+    pub fn new() -> Self {
+        Self {
+            position: 0,
+            pressed: false,
+            phase: 0,
+            pending_transitions: 0,
+            keys: None,
+            button_key: None,
+        }
+    }
+
+    /// Binds keys to turn the encoder counterclockwise and clockwise.
+    pub fn with_keys(mut self, counterclockwise: Keycode, clockwise: Keycode) -> Self {
+        self.keys = Some((counterclockwise, clockwise));
+
+        self
+    }
+
+    /// Binds a key to the push button.
+    pub fn with_button_key(mut self, keycode: Keycode) -> Self {
+        self.button_key = Some(keycode);
+
+        self
+    }
+
+    /// Updates the encoder state based on a simulator event.
+    pub fn handle_event(&mut self, event: &SimulatorEvent) {
+        match *event {
+            SimulatorEvent::MouseWheel {
+                scroll_delta,
+                direction,
+                ..
+            } => {
+                let detents = match direction {
+                    MouseWheelDirection::Flipped => -scroll_delta.y,
+                    _ => scroll_delta.y,
+                };
+                self.turn(detents);
+            }
+            SimulatorEvent::KeyDown { keycode, .. } => {
+                if let Some((counterclockwise, clockwise)) = self.keys {
+                    if keycode == counterclockwise {
+                        self.turn(-1);
+                    } else if keycode == clockwise {
+                        self.turn(1);
+                    }
+                }
+
+                if self.button_key == Some(keycode) {
+                    self.pressed = true;
+                }
+            }
+            SimulatorEvent::KeyUp { keycode, .. } if self.button_key == Some(keycode) => {
+                self.pressed = false;
+            }
+            SimulatorEvent::MouseButtonDown {
+                mouse_btn: MouseButton::Middle,
+                ..
+            } => self.pressed = true,
+            SimulatorEvent::MouseButtonUp {
+                mouse_btn: MouseButton::Middle,
+                ..
+            } => self.pressed = false,
+            _ => {}
+        }
+    }
+
+    /// Turns the encoder by the given number of detents.
+    ///
+    /// Positive values turn the encoder clockwise.
+    pub fn turn(&mut self, detents: i32) {
+        self.position = self.position.wrapping_add(detents);
+        self.pending_transitions = self
+            .pending_transitions
+            .saturating_add(detents.saturating_mul(TRANSITIONS_PER_DETENT));
+    }
+
+    /// Returns the encoder position in detents.
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Sets the encoder position.
+    ///
+    /// This doesn't affect the quadrature signals.
+    pub fn set_position(&mut self, position: i32) {
+        self.position = position;
+    }
+
+    /// Returns `true` if the push button is pressed.
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Applies the next pending quadrature transition.
+    ///
+    /// Returns `false` if no transitions were pending.
+    pub fn step(&mut self) -> bool {
+        let direction = self.pending_transitions.signum();
+        if direction == 0 {
+            return false;
+        }
+
+        self.pending_transitions -= direction;
+        self.phase = (self.phase + direction).rem_euclid(TRANSITIONS_PER_DETENT);
+
+        true
+    }
+
+    /// Returns the number of pending quadrature transitions.
+    ///
+    /// Positive values indicate clockwise transitions.
+    pub fn pending_transitions(&self) -> i32 {
+        self.pending_transitions
+    }
+
+    /// Returns the level of the A pin.
+    ///
+    /// Both pins are low while the encoder rests in a detent. When the encoder
+    /// is turned clockwise the A signal leads the B signal.
+    pub fn pin_a(&self) -> bool {
+        matches!(self.phase, 1 | 2)
+    }
+
+    /// Returns the level of the B pin.
+    pub fn pin_b(&self) -> bool {
+        matches!(self.phase, 2 | 3)
+    }
+}
+
+impl Default for RotaryEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use embedded_graphics::prelude::*;
+
+    fn wheel(y: i32) -> SimulatorEvent {
+        SimulatorEvent::MouseWheel {
+            scroll_delta: Point::new(0, y),
+            direction: MouseWheelDirection::Normal,
+            point: Point::zero(),
+        }
+    }
+
+    fn pins(encoder: &RotaryEncoder) -> (bool, bool) {
+        (encoder.pin_a(), encoder.pin_b())
+    }
+
+    #[test]
+    fn position() {
+        let mut encoder = RotaryEncoder::new();
+
+        encoder.handle_event(&wheel(2));
+        assert_eq!(encoder.position(), 2);
+
+        encoder.handle_event(&wheel(-3));
+        assert_eq!(encoder.position(), -1);
+        assert_eq!(encoder.pending_transitions(), -4);
+    }
+
+    #[test]
+    fn quadrature_signals() {
+        let mut encoder = RotaryEncoder::new();
+        encoder.turn(1);
+
+        let mut states = vec![pins(&encoder)];
+        while encoder.step() {
+            states.push(pins(&encoder));
+        }
+
+        assert_eq!(
+            states,
+            [
+                (false, false),
+                (true, false),
+                (true, true),
+                (false, true),
+                (false, false)
+            ]
+        );
+
+        encoder.turn(-1);
+        assert!(encoder.step());
+        assert_eq!(pins(&encoder), (false, true));
+    }
+}
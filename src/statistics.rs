@@ -0,0 +1,201 @@
+use std::{convert::TryFrom, ops::Range};
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use crate::{
+    display::SimulatorDisplay, output_image::OutputImage, output_settings::OutputSettings,
+};
+
+/// Colors used for the overdraw heatmap.
+///
+/// The index into this array is the number of writes to a pixel. Pixels that
+/// were written more often than the length of the array use the last color.
+const HEATMAP_COLORS: [Rgb888; 5] = [
+    Rgb888::BLACK,
+    Rgb888::new(0, 96, 0),
+    Rgb888::new(255, 255, 0),
+    Rgb888::new(255, 128, 0),
+    Rgb888::new(255, 0, 0),
+];
+
+/// Draw statistics.
+///
+/// Draw statistics record how often the individual [`DrawTarget`] methods of a
+/// [`SimulatorDisplay`] were called and how often each pixel was written to.
+/// This can be used to find code that redraws the same pixels multiple times,
+/// which can be expensive on real displays with slow interfaces.
+///
+/// Statistics are disabled by default and need to be enabled by calling
+/// [`SimulatorDisplay::enable_statistics`]. The statistics accumulate until
+/// [`SimulatorDisplay::reset_statistics`] is called, which is usually done once
+/// per frame.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{
+///     pixelcolor::BinaryColor,
+///     prelude::*,
+///     primitives::{PrimitiveStyle, Rectangle},
+/// };
+/// use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay};
+///
+/// let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(64, 64));
+/// display.enable_statistics();
+///
+/// display.clear(BinaryColor::Off).unwrap();
+/// Rectangle::new(Point::new(8, 8), Size::new(16, 16))
+///     .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+///     .draw(&mut display)
+///     .unwrap();
+///
+/// let statistics = display.statistics().unwrap();
+/// assert_eq!(statistics.clear_calls(), 1);
+/// assert_eq!(statistics.fill_solid_calls(), 1);
+/// assert_eq!(statistics.pixel_writes(Point::new(10, 10)), 2);
+/// assert_eq!(statistics.overdrawn_pixels(), 16 * 16);
+///
+/// let heatmap = statistics.to_heatmap_output_image(&OutputSettings::default());
+/// // example: heatmap.save_png("heatmap.png")?;
+///
+/// display.reset_statistics();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrawStatistics {
+    size: Size,
+    pixel_writes: Box<[u32]>,
+    draw_iter_calls: u64,
+    fill_contiguous_calls: u64,
+    fill_solid_calls: u64,
+    clear_calls: u64,
+}
+
+impl DrawStatistics {
+    pub(crate) fn new(size: Size) -> Self {
+        let pixel_count = size.width as usize * size.height as usize;
+
+        Self {
+            size,
+            pixel_writes: vec![0; pixel_count].into_boxed_slice(),
+            draw_iter_calls: 0,
+            fill_contiguous_calls: 0,
+            fill_solid_calls: 0,
+            clear_calls: 0,
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.pixel_writes.fill(0);
+        self.draw_iter_calls = 0;
+        self.fill_contiguous_calls = 0;
+        self.fill_solid_calls = 0;
+        self.clear_calls = 0;
+    }
+
+    pub(crate) fn record_write(&mut self, index: usize) {
+        self.pixel_writes[index] = self.pixel_writes[index].saturating_add(1);
+    }
+
+    pub(crate) fn record_writes(&mut self, range: Range<usize>) {
+        for writes in &mut self.pixel_writes[range] {
+            *writes = writes.saturating_add(1);
+        }
+    }
+
+    pub(crate) fn record_draw_iter(&mut self) {
+        self.draw_iter_calls += 1;
+    }
+
+    pub(crate) fn record_fill_contiguous(&mut self) {
+        self.fill_contiguous_calls += 1;
+    }
+
+    pub(crate) fn record_fill_solid(&mut self) {
+        self.fill_solid_calls += 1;
+    }
+
+    pub(crate) fn record_clear(&mut self) {
+        self.clear_calls += 1;
+    }
+
+    /// Returns the number of [`DrawTarget::draw_iter`] calls.
+    pub fn draw_iter_calls(&self) -> u64 {
+        self.draw_iter_calls
+    }
+
+    /// Returns the number of [`DrawTarget::fill_contiguous`] calls.
+    pub fn fill_contiguous_calls(&self) -> u64 {
+        self.fill_contiguous_calls
+    }
+
+    /// Returns the number of [`DrawTarget::fill_solid`] calls.
+    pub fn fill_solid_calls(&self) -> u64 {
+        self.fill_solid_calls
+    }
+
+    /// Returns the number of [`DrawTarget::clear`] calls.
+    pub fn clear_calls(&self) -> u64 {
+        self.clear_calls
+    }
+
+    /// Returns the number of writes to the pixel at a point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `point` is outside the display.
+    pub fn pixel_writes(&self, point: Point) -> u32 {
+        <(u32, u32)>::try_from(point)
+            .ok()
+            .filter(|(x, y)| *x < self.size.width && *y < self.size.height)
+            .map(|(x, y)| self.pixel_writes[(x + y * self.size.width) as usize])
+            .expect("can't get point outside of display")
+    }
+
+    /// Returns the total number of pixel writes.
+    pub fn total_writes(&self) -> u64 {
+        self.pixel_writes
+            .iter()
+            .map(|writes| u64::from(*writes))
+            .sum()
+    }
+
+    /// Returns the highest number of writes to a single pixel.
+    pub fn max_writes(&self) -> u32 {
+        self.pixel_writes.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the number of pixels that were written more than once.
+    pub fn overdrawn_pixels(&self) -> usize {
+        self.pixel_writes
+            .iter()
+            .filter(|writes| **writes > 1)
+            .count()
+    }
+
+    /// Converts the pixel write counts into a heatmap image.
+    ///
+    /// Pixels that weren't written are black, pixels written once are dark
+    /// green and pixels that were written multiple times are shown in yellow,
+    /// orange and red, with red being used for pixels that were written four
+    /// or more times.
+    ///
+    /// The [`scale`](OutputSettings::scale) and
+    /// [`pixel_spacing`](OutputSettings::pixel_spacing) settings are applied to
+    /// the heatmap, but the [`theme`](OutputSettings::theme) is ignored.
+    pub fn to_heatmap_output_image(&self, output_settings: &OutputSettings) -> OutputImage<Rgb888> {
+        let output_settings = OutputSettings {
+            theme: Default::default(),
+            ..*output_settings
+        };
+
+        let mut heatmap = SimulatorDisplay::with_default_color(self.size, Rgb888::BLACK);
+        for (pixel, writes) in heatmap.pixels.iter_mut().zip(self.pixel_writes.iter()) {
+            let index = usize::try_from(*writes)
+                .unwrap_or(usize::MAX)
+                .min(HEATMAP_COLORS.len() - 1);
+            *pixel = HEATMAP_COLORS[index];
+        }
+
+        heatmap.to_rgb_output_image(&output_settings)
+    }
+}
@@ -0,0 +1,250 @@
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::{Circle, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle},
+};
+
+use crate::{
+    display::bounding_box_union,
+    layout::{caption_area, caption_text},
+};
+
+/// Border color of LEDs.
+const LED_BORDER_COLOR: Rgb888 = Rgb888::CSS_DARK_GRAY;
+
+/// Clickable virtual hardware button.
+///
+/// Virtual buttons are drawn into the window outside of the displays and are
+/// used to simulate physical buttons next to the display. Clicking a button
+/// with the left mouse button generates
+/// [`VirtualButtonDown`](crate::SimulatorEvent::VirtualButtonDown) and
+/// [`VirtualButtonUp`](crate::SimulatorEvent::VirtualButtonUp) events with the
+/// ID of the button.
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{
+///     pixelcolor::Rgb888,
+///     prelude::*,
+///     primitives::{Circle, Rectangle},
+/// };
+/// use embedded_graphics_simulator::VirtualButton;
+///
+/// let ok_button = VirtualButton::rectangle(0, Rectangle::new(Point::new(10, 80), Size::new(30, 15)))
+///     .label("OK");
+/// let reset_button = VirtualButton::circle(1, Circle::new(Point::new(60, 80), 15))
+///     .label("RST")
+///     .color(Rgb888::CSS_DARK_RED);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualButton {
+    pub(crate) id: u32,
+    shape: ButtonShape,
+    label: Option<String>,
+    color: Rgb888,
+}
+
+impl VirtualButton {
+    /// Creates a new rectangular button.
+    pub fn rectangle(id: u32, rectangle: Rectangle) -> Self {
+        Self::new(id, ButtonShape::Rectangle(rectangle))
+    }
+
+    /// Creates a new circular button.
+    pub fn circle(id: u32, circle: Circle) -> Self {
+        Self::new(id, ButtonShape::Circle(circle))
+    }
+
+    fn new(id: u32, shape: ButtonShape) -> Self {
+        Self {
+            id,
+            shape,
+            label: None,
+            color: Rgb888::CSS_GRAY,
+        }
+    }
+
+    /// Sets the label, which is shown below the button.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(String::from(label));
+        self
+    }
+
+    /// Sets the fill color of the button.
+    ///
+    /// The default color is gray.
+    pub fn color(mut self, color: Rgb888) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the clickable area of the button.
+    #[cfg(feature = "with-sdl")]
+    pub(crate) fn button_area(&self) -> ButtonArea {
+        ButtonArea {
+            id: self.id,
+            shape: self.shape,
+        }
+    }
+
+    /// Returns the area that is covered by the button and the label.
+    pub(crate) fn bounding_box(&self) -> Rectangle {
+        label_bounding_box(&self.shape.bounding_box(), self.label.as_deref())
+    }
+
+    pub(crate) fn draw<D>(&self, target: &mut D, label_color: Rgb888)
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        self.shape.fill(self.color, target);
+        draw_label(
+            target,
+            &self.shape.bounding_box(),
+            self.label.as_deref(),
+            label_color,
+        );
+    }
+}
+
+/// LED indicator.
+///
+/// LEDs are drawn into the window outside of the displays and are used to
+/// simulate status LEDs next to the display. The color of an LED can be
+/// changed at any time by using its ID, for example with
+/// [`MultiWindow::set_led_color`](crate::MultiWindow::set_led_color).
+///
+/// # Examples
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Circle};
+/// use embedded_graphics_simulator::Led;
+///
+/// let power_led = Led::new(0, Circle::new(Point::new(10, 80), 8))
+///     .label("PWR")
+///     .color(Rgb888::GREEN);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Led {
+    pub(crate) id: u32,
+    circle: Circle,
+    label: Option<String>,
+    pub(crate) color: Rgb888,
+}
+
+impl Led {
+    /// Creates a new LED.
+    ///
+    /// The LED is initially black, which represents an LED that is turned off.
+    pub fn new(id: u32, circle: Circle) -> Self {
+        Self {
+            id,
+            circle,
+            label: None,
+            color: Rgb888::BLACK,
+        }
+    }
+
+    /// Sets the label, which is shown below the LED.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(String::from(label));
+        self
+    }
+
+    /// Sets the initial color of the LED.
+    pub fn color(mut self, color: Rgb888) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the area that is covered by the LED and the label.
+    pub(crate) fn bounding_box(&self) -> Rectangle {
+        label_bounding_box(&self.circle.bounding_box(), self.label.as_deref())
+    }
+
+    pub(crate) fn draw<D>(&self, target: &mut D, label_color: Rgb888)
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        let style = PrimitiveStyleBuilder::new()
+            .fill_color(self.color)
+            .stroke_color(LED_BORDER_COLOR)
+            .stroke_width(1)
+            .build();
+
+        self.circle.into_styled(style).draw(target).ok();
+        draw_label(
+            target,
+            &self.circle.bounding_box(),
+            self.label.as_deref(),
+            label_color,
+        );
+    }
+}
+
+/// Shape of a clickable button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ButtonShape {
+    Rectangle(Rectangle),
+    Circle(Circle),
+}
+
+impl ButtonShape {
+    #[cfg(feature = "with-sdl")]
+    pub(crate) fn contains(&self, point: Point) -> bool {
+        match self {
+            Self::Rectangle(rectangle) => rectangle.contains(point),
+            Self::Circle(circle) => circle.contains(point),
+        }
+    }
+
+    #[cfg(feature = "with-sdl")]
+    pub(crate) fn translate(&self, by: Point) -> Self {
+        match self {
+            Self::Rectangle(rectangle) => Self::Rectangle(rectangle.translate(by)),
+            Self::Circle(circle) => Self::Circle(circle.translate(by)),
+        }
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        match self {
+            Self::Rectangle(rectangle) => *rectangle,
+            Self::Circle(circle) => circle.bounding_box(),
+        }
+    }
+
+    fn fill<D>(&self, color: Rgb888, target: &mut D)
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        let style = PrimitiveStyle::with_fill(color);
+
+        match self {
+            Self::Rectangle(rectangle) => rectangle.into_styled(style).draw(target).ok(),
+            Self::Circle(circle) => circle.into_styled(style).draw(target).ok(),
+        };
+    }
+}
+
+/// Clickable area, which generates virtual button events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ButtonArea {
+    pub(crate) id: u32,
+    pub(crate) shape: ButtonShape,
+}
+
+fn label_bounding_box(shape_area: &Rectangle, label: Option<&str>) -> Rectangle {
+    match label {
+        Some(label) => bounding_box_union(shape_area, &caption_area(label, shape_area)),
+        None => *shape_area,
+    }
+}
+
+fn draw_label<D>(target: &mut D, shape_area: &Rectangle, label: Option<&str>, color: Rgb888)
+where
+    D: DrawTarget<Color = Rgb888>,
+{
+    if let Some(label) = label {
+        caption_text(label, shape_area, color).draw(target).ok();
+    }
+}
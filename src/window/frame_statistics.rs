@@ -0,0 +1,104 @@
+use std::{fmt, time::Duration};
+
+/// Weight of the latest frame in the smoothed frame rate.
+const SMOOTHING_FACTOR: f32 = 0.1;
+
+/// Frame rate and timing statistics.
+///
+/// The statistics are measured by the simulator window and can be used to
+/// check whether the rendering code of an application would keep up on a
+/// slower target. They are returned by
+/// [`Window::frame_statistics`](crate::Window::frame_statistics) and
+/// [`MultiWindow::frame_statistics`](crate::MultiWindow::frame_statistics).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStatistics {
+    fps: f32,
+    frame_time: Duration,
+    app_time: Duration,
+}
+
+impl FrameStatistics {
+    /// Returns the measured frame rate in frames per second.
+    ///
+    /// The frame rate is smoothed over multiple frames to make it readable if
+    /// it is shown continuously.
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    /// Returns the duration of the last frame.
+    ///
+    /// The frame time is the time between the last two window updates,
+    /// including the time the simulator waited to limit the frame rate.
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    /// Returns the time spent in the application during the last frame.
+    ///
+    /// This is the time between the end of the previous window update and the
+    /// start of the last window update, which doesn't include the time the
+    /// simulator waited to limit the frame rate.
+    pub fn app_time(&self) -> Duration {
+        self.app_time
+    }
+
+    /// Updates the statistics with the timing of a new frame.
+    pub(crate) fn record_frame(&mut self, frame_time: Duration, app_time: Duration) {
+        self.frame_time = frame_time;
+        self.app_time = app_time;
+
+        let frame_time = frame_time.as_secs_f32();
+        if frame_time > 0.0 {
+            let fps = 1.0 / frame_time;
+            self.fps = if self.fps == 0.0 {
+                fps
+            } else {
+                self.fps + (fps - self.fps) * SMOOTHING_FACTOR
+            };
+        }
+    }
+}
+
+impl fmt::Display for FrameStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1} FPS, frame time {:.1} ms, app time {:.1} ms",
+            self.fps,
+            self.frame_time.as_secs_f32() * 1000.0,
+            self.app_time.as_secs_f32() * 1000.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_frame() {
+        let mut statistics = FrameStatistics::default();
+
+        statistics.record_frame(Duration::from_millis(20), Duration::from_millis(5));
+        assert_eq!(statistics.fps(), 50.0);
+        assert_eq!(statistics.frame_time(), Duration::from_millis(20));
+        assert_eq!(statistics.app_time(), Duration::from_millis(5));
+
+        statistics.record_frame(Duration::from_millis(10), Duration::from_millis(8));
+        assert_eq!(statistics.fps(), 55.0);
+        assert_eq!(statistics.frame_time(), Duration::from_millis(10));
+        assert_eq!(statistics.app_time(), Duration::from_millis(8));
+    }
+
+    #[test]
+    fn display() {
+        let mut statistics = FrameStatistics::default();
+        statistics.record_frame(Duration::from_millis(20), Duration::from_millis(5));
+
+        assert_eq!(
+            statistics.to_string(),
+            "50.0 FPS, frame time 20.0 ms, app time 5.0 ms"
+        );
+    }
+}
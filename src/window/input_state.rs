@@ -0,0 +1,261 @@
+use embedded_graphics::prelude::*;
+use sdl2::{
+    keyboard::{Keycode, Mod},
+    mouse::MouseButton,
+};
+
+/// Keyboard state.
+///
+/// The keyboard state contains the keys which were held down at the time the
+/// state was queried. See [`Window::keyboard_state`](crate::Window::keyboard_state)
+/// and [`MultiWindow::keyboard_state`](crate::MultiWindow::keyboard_state) for
+/// more details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyboardState {
+    pressed_keys: Vec<Keycode>,
+    keymod: Mod,
+}
+
+impl KeyboardState {
+    pub(crate) fn new(pressed_keys: Vec<Keycode>, keymod: Mod) -> Self {
+        Self {
+            pressed_keys,
+            keymod,
+        }
+    }
+
+    /// Returns `true` if the given key is pressed.
+    pub fn is_pressed(&self, keycode: Keycode) -> bool {
+        self.pressed_keys.contains(&keycode)
+    }
+
+    /// Returns all pressed keys.
+    pub fn pressed_keys(&self) -> &[Keycode] {
+        &self.pressed_keys
+    }
+
+    /// Returns the active modifiers.
+    pub fn keymod(&self) -> Mod {
+        self.keymod
+    }
+}
+
+/// Mouse state.
+///
+/// The mouse state contains the mouse position and the buttons which were held
+/// down at the time the state was queried. See
+/// [`Window::mouse_state`](crate::Window::mouse_state) and
+/// [`MultiWindow::mouse_state`](crate::MultiWindow::mouse_state) for more
+/// details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseState {
+    position: Point,
+    pressed_buttons: MouseButtons,
+}
+
+impl MouseState {
+    pub(crate) fn new(position: Point, pressed_buttons: MouseButtons) -> Self {
+        Self {
+            position,
+            pressed_buttons,
+        }
+    }
+
+    /// Returns the mouse position.
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    /// Returns `true` if the given button is pressed.
+    pub fn is_button_pressed(&self, mouse_button: MouseButton) -> bool {
+        self.pressed_buttons.contains(mouse_button)
+    }
+
+    /// Returns all pressed buttons.
+    pub fn pressed_buttons(&self) -> MouseButtons {
+        self.pressed_buttons
+    }
+}
+
+/// Mouse buttons which can be stored in a `MouseButtons` mask.
+const MASK_BUTTONS: [MouseButton; 5] = [
+    MouseButton::Left,
+    MouseButton::Middle,
+    MouseButton::Right,
+    MouseButton::X1,
+    MouseButton::X2,
+];
+
+/// Set of pressed mouse buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MouseButtons(u8);
+
+impl MouseButtons {
+    /// Creates an empty set of mouse buttons.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if the given button is part of the set.
+    pub fn contains(self, mouse_button: MouseButton) -> bool {
+        self.0 & Self::bit(mouse_button) != 0
+    }
+
+    /// Returns `true` if no button is part of the set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Adds a button to the set.
+    #[must_use]
+    pub fn with(self, mouse_button: MouseButton) -> Self {
+        Self(self.0 | Self::bit(mouse_button))
+    }
+
+    /// Removes a button from the set.
+    #[must_use]
+    pub fn without(self, mouse_button: MouseButton) -> Self {
+        Self(self.0 & !Self::bit(mouse_button))
+    }
+
+    /// Returns an iterator over the buttons in the set.
+    pub fn iter(self) -> impl Iterator<Item = MouseButton> {
+        MASK_BUTTONS
+            .into_iter()
+            .filter(move |mouse_button| self.contains(*mouse_button))
+    }
+
+    fn bit(mouse_button: MouseButton) -> u8 {
+        MASK_BUTTONS
+            .iter()
+            .position(|button| *button == mouse_button)
+            .map_or(0, |index| 1 << index)
+    }
+}
+
+impl From<sdl2::mouse::MouseState> for MouseButtons {
+    fn from(mouse_state: sdl2::mouse::MouseState) -> Self {
+        mouse_state
+            .pressed_mouse_buttons()
+            .fold(Self::empty(), Self::with)
+    }
+}
+
+/// Modifier groups which are compared by `KeyCombination::matches`.
+///
+/// The left and right variant of a modifier are treated as equivalent.
+const MODIFIER_GROUPS: [Mod; 4] = [
+    Mod::LCTRLMOD.union(Mod::RCTRLMOD),
+    Mod::LSHIFTMOD.union(Mod::RSHIFTMOD),
+    Mod::LALTMOD.union(Mod::RALTMOD),
+    Mod::LGUIMOD.union(Mod::RGUIMOD),
+];
+
+/// Key combination.
+///
+/// A key combination consists of a key and a set of modifiers. When a key
+/// combination is compared to a key press, the left and right variants of
+/// modifiers are treated as equivalent and the state of lock keys, like caps
+/// lock, is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyCombination {
+    keycode: Keycode,
+    keymod: Mod,
+    any_keymod: bool,
+}
+
+impl KeyCombination {
+    /// Creates a new key combination.
+    pub const fn new(keycode: Keycode, keymod: Mod) -> Self {
+        Self {
+            keycode,
+            keymod,
+            any_keymod: false,
+        }
+    }
+
+    /// Creates a new key combination that matches a key regardless of the modifiers.
+    pub const fn any_modifiers(keycode: Keycode) -> Self {
+        Self {
+            keycode,
+            keymod: Mod::NOMOD,
+            any_keymod: true,
+        }
+    }
+
+    /// Returns the key.
+    pub const fn keycode(&self) -> Keycode {
+        self.keycode
+    }
+
+    /// Returns the modifiers.
+    ///
+    /// Returns [`Mod::NOMOD`] for key combinations that were created with
+    /// [`any_modifiers`](Self::any_modifiers).
+    pub const fn keymod(&self) -> Mod {
+        self.keymod
+    }
+
+    /// Returns `true` if a key press matches this key combination.
+    pub fn matches(&self, keycode: Keycode, keymod: Mod) -> bool {
+        keycode == self.keycode
+            && (self.any_keymod
+                || MODIFIER_GROUPS
+                    .iter()
+                    .all(|group| self.keymod.intersects(*group) == keymod.intersects(*group)))
+    }
+}
+
+impl From<Keycode> for KeyCombination {
+    fn from(keycode: Keycode) -> Self {
+        Self::new(keycode, Mod::NOMOD)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mouse_buttons() {
+        let buttons = MouseButtons::empty()
+            .with(MouseButton::Right)
+            .with(MouseButton::Left);
+
+        assert!(buttons.contains(MouseButton::Left));
+        assert!(buttons.contains(MouseButton::Right));
+        assert!(!buttons.contains(MouseButton::Middle));
+        assert!(!buttons.is_empty());
+        assert_eq!(
+            buttons.iter().collect::<Vec<_>>(),
+            [MouseButton::Left, MouseButton::Right]
+        );
+
+        assert!(MouseButtons::empty().with(MouseButton::Unknown).is_empty());
+        assert_eq!(
+            buttons.without(MouseButton::Right),
+            MouseButtons::empty().with(MouseButton::Left)
+        );
+    }
+
+    #[test]
+    fn key_combination_matches() {
+        let ctrl_q = KeyCombination::new(Keycode::Q, Mod::LCTRLMOD);
+
+        assert!(ctrl_q.matches(Keycode::Q, Mod::LCTRLMOD));
+        assert!(ctrl_q.matches(Keycode::Q, Mod::RCTRLMOD));
+        assert!(ctrl_q.matches(Keycode::Q, Mod::LCTRLMOD | Mod::CAPSMOD));
+        assert!(!ctrl_q.matches(Keycode::Q, Mod::NOMOD));
+        assert!(!ctrl_q.matches(Keycode::Q, Mod::LCTRLMOD | Mod::LSHIFTMOD));
+        assert!(!ctrl_q.matches(Keycode::W, Mod::LCTRLMOD));
+
+        let escape = KeyCombination::from(Keycode::Escape);
+        assert!(escape.matches(Keycode::Escape, Mod::NUMMOD));
+        assert!(!escape.matches(Keycode::Escape, Mod::RALTMOD));
+
+        let any_escape = KeyCombination::any_modifiers(Keycode::Escape);
+        assert!(any_escape.matches(Keycode::Escape, Mod::NOMOD));
+        assert!(any_escape.matches(Keycode::Escape, Mod::LSHIFTMOD | Mod::RCTRLMOD));
+        assert!(!any_escape.matches(Keycode::Q, Mod::NOMOD));
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     fs::File,
     io::BufReader,
     ops::Deref,
+    path::Path,
     process, thread,
     time::{Duration, Instant},
 };
@@ -10,56 +11,214 @@ use std::{
 use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
 
 use crate::{
-    display::SimulatorDisplay, output_image::OutputImage, output_settings::OutputSettings,
+    bezel::Bezel, display::SimulatorDisplay, output_image::OutputImage,
+    output_settings::OutputSettings,
 };
 
 #[cfg(feature = "with-sdl")]
 mod sdl_window;
 
 #[cfg(feature = "with-sdl")]
-pub use sdl_window::{SdlWindow, SimulatorEvent, SimulatorEventsIter};
+use sdl_window::WindowOptions;
+#[cfg(feature = "with-sdl")]
+pub use sdl_window::{
+    EventSettings, SdlWindow, SimulatorEvent, SimulatorEventsIter, MOUSE_FINGER_ID, PINCH_FINGER_ID,
+};
+
+#[cfg(feature = "with-sdl")]
+mod input_state;
+
+#[cfg(feature = "with-sdl")]
+pub use input_state::{KeyCombination, KeyboardState, MouseButtons, MouseState};
 
 #[cfg(feature = "with-sdl")]
 mod multi_window;
 
+#[cfg(any(feature = "with-sdl", test))]
+mod view;
+
 #[cfg(feature = "with-sdl")]
-pub use multi_window::MultiWindow;
+pub use multi_window::{DisplayEvent, MultiWindow};
+
+mod frame_statistics;
+
+pub use frame_statistics::FrameStatistics;
+
+/// Minimum time between updates of the frame statistics in the window title.
+#[cfg(feature = "with-sdl")]
+const TITLE_STATISTICS_INTERVAL: Duration = Duration::from_millis(500);
 
 pub(crate) struct FpsLimiter {
     max_fps: u32,
     frame_start: Instant,
+    /// Advance the clock by exactly one frame per update without sleeping.
+    fast_mode: bool,
+    /// Simulated time at the last update.
+    now: Duration,
+    /// Simulated time at the start of the current clock segment.
+    ///
+    /// A new segment is started every time the FPS limit or the mode is
+    /// changed, which keeps the clock continuous.
+    segment_time: Duration,
+    /// Real time at the start of the current clock segment.
+    segment_start: Instant,
+    /// Number of updates since the start of the current clock segment.
+    segment_frames: u64,
+    /// Time at which the current window update was started.
+    update_start: Option<Instant>,
+    statistics: FrameStatistics,
+    /// Show the frame statistics in the window title.
+    #[cfg(feature = "with-sdl")]
+    show_statistics: bool,
+    /// Time at which the frame statistics were last shown in the window title.
+    #[cfg(feature = "with-sdl")]
+    title_update: Option<Instant>,
 }
 
 impl FpsLimiter {
     pub(crate) fn new() -> Self {
+        let now = Instant::now();
+
         Self {
             max_fps: 60,
-            frame_start: Instant::now(),
+            frame_start: now,
+            fast_mode: env::var("EG_SIMULATOR_FAST").is_ok_and(|value| value == "1"),
+            now: Duration::ZERO,
+            segment_time: Duration::ZERO,
+            segment_start: now,
+            segment_frames: 0,
+            update_start: None,
+            statistics: FrameStatistics::default(),
+            #[cfg(feature = "with-sdl")]
+            show_statistics: false,
+            #[cfg(feature = "with-sdl")]
+            title_update: None,
         }
     }
 
+    /// Marks the start of a window update.
+    ///
+    /// The time between the end of the previous update and this call is
+    /// recorded as the app time.
+    fn start_update(&mut self) {
+        self.update_start = Some(Instant::now());
+    }
+
     fn desired_loop_duration(&self) -> Duration {
         Duration::from_secs_f32(1.0 / self.max_fps as f32)
     }
 
     fn sleep(&mut self) {
-        let sleep_duration = (self.frame_start + self.desired_loop_duration())
-            .saturating_duration_since(Instant::now());
-        thread::sleep(sleep_duration);
+        let update_start = self.update_start.take().unwrap_or_else(Instant::now);
+        let app_time = update_start.saturating_duration_since(self.frame_start);
+
+        if !self.fast_mode {
+            let sleep_duration = (self.frame_start + self.desired_loop_duration())
+                .saturating_duration_since(Instant::now());
+            thread::sleep(sleep_duration);
+        }
+
+        let now = Instant::now();
+        self.statistics
+            .record_frame(now.saturating_duration_since(self.frame_start), app_time);
+        self.frame_start = now;
+
+        self.segment_frames += 1;
+        self.now = self.segment_time
+            + if self.fast_mode {
+                Duration::from_secs(self.segment_frames) / self.max_fps
+            } else {
+                now.saturating_duration_since(self.segment_start)
+            };
+    }
+
+    /// Starts a new clock segment at the current simulated time.
+    fn start_segment(&mut self) {
+        self.segment_time = self.now;
+        self.segment_start = Instant::now();
+        self.segment_frames = 0;
+    }
+
+    fn set_max_fps(&mut self, max_fps: u32) {
+        assert!(max_fps > 0, "max_fps must be greater than 0");
+
+        self.max_fps = max_fps;
+        self.start_segment();
+    }
+
+    fn set_fast_mode(&mut self, fast_mode: bool) {
+        self.fast_mode = fast_mode;
+        self.start_segment();
+    }
+
+    /// Returns the window title with the frame statistics.
+    ///
+    /// Returns `None` if the title doesn't need to be updated, because showing
+    /// the statistics is disabled or the title was updated recently.
+    #[cfg(feature = "with-sdl")]
+    fn statistics_title(&mut self, title: &str) -> Option<String> {
+        if !self.show_statistics
+            || self
+                .title_update
+                .is_some_and(|title_update| title_update.elapsed() < TITLE_STATISTICS_INTERVAL)
+        {
+            return None;
+        }
 
-        self.frame_start = Instant::now();
+        self.title_update = Some(Instant::now());
+
+        Some(format!("{title} ({})", self.statistics))
+    }
+
+    /// Enables or disables the frame statistics in the window title.
+    ///
+    /// Returns the title that should be shown if the setting was changed.
+    #[cfg(feature = "with-sdl")]
+    fn set_show_statistics(&mut self, show_statistics: bool, title: &str) -> Option<String> {
+        if self.show_statistics == show_statistics {
+            return None;
+        }
+
+        self.show_statistics = show_statistics;
+        self.title_update = None;
+
+        if show_statistics {
+            self.statistics_title(title)
+        } else {
+            Some(String::from(title))
+        }
     }
 }
 
 /// Simulator window
+///
+/// Multiple windows can be used at the same time, for example to simulate a
+/// device with a separate remote control. All windows share the SDL event
+/// pump and each window only returns the events that belong to it from
+/// [`events`](Self::events).
 #[allow(dead_code)]
 pub struct Window {
     framebuffer: Option<OutputImage<Rgb888>>,
     #[cfg(feature = "with-sdl")]
+    sdl_context: Option<sdl2::Sdl>,
+    #[cfg(feature = "with-sdl")]
     sdl_window: Option<SdlWindow>,
+    #[cfg(feature = "with-sdl")]
+    event_settings: EventSettings,
+    #[cfg(feature = "with-sdl")]
+    window_options: WindowOptions,
     title: String,
     output_settings: OutputSettings,
+    bezel: Option<Bezel>,
     fps_limiter: FpsLimiter,
+    /// ID of the display that was shown in the last update.
+    last_display_id: Option<usize>,
+    /// Dirty area generation of the display that was shown in the last update.
+    #[cfg(feature = "with-sdl")]
+    dirty_generation: u64,
+    /// Area of the display in the window.
+    #[cfg(feature = "with-sdl")]
+    display_area: embedded_graphics::primitives::Rectangle,
 }
 
 impl Window {
@@ -68,44 +227,79 @@ impl Window {
         Self {
             framebuffer: None,
             #[cfg(feature = "with-sdl")]
+            sdl_context: None,
+            #[cfg(feature = "with-sdl")]
             sdl_window: None,
+            #[cfg(feature = "with-sdl")]
+            event_settings: EventSettings::default(),
+            #[cfg(feature = "with-sdl")]
+            window_options: WindowOptions::default(),
             title: String::from(title),
-            output_settings: output_settings.clone(),
+            output_settings: *output_settings,
+            bezel: None,
             fps_limiter: FpsLimiter::new(),
+            last_display_id: None,
+            #[cfg(feature = "with-sdl")]
+            dirty_generation: 0,
+            #[cfg(feature = "with-sdl")]
+            display_area: embedded_graphics::primitives::Rectangle::zero(),
         }
     }
 
+    /// Creates a new simulator window using an existing SDL context.
+    ///
+    /// By default the simulator initializes its own SDL context. This
+    /// constructor can be used to share a context between the simulator and
+    /// other parts of the application, for example to use the SDL audio
+    /// subsystem next to the simulator window.
+    ///
+    /// The window claims the SDL [`EventPump`](sdl2::EventPump) when it is
+    /// first updated. The application must not create its own event pump,
+    /// because SDL only allows one event pump to exist at a time. Use
+    /// [`events`](Self::events) to receive events instead.
+    #[cfg(feature = "with-sdl")]
+    pub fn with_sdl_context(
+        sdl_context: &sdl2::Sdl,
+        title: &str,
+        output_settings: &OutputSettings,
+    ) -> Self {
+        let mut window = Self::new(title, output_settings);
+        window.sdl_context = Some(sdl_context.clone());
+
+        window
+    }
+
+    /// Returns the SDL context used by this window.
+    ///
+    /// The context is initialized by this method if it wasn't initialized
+    /// before. It can be used to access other SDL subsystems, like audio or
+    /// timers, from the same context the simulator uses.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics_simulator::{OutputSettings, Window};
+    ///
+    /// let mut window = Window::new("Audio", &OutputSettings::default());
+    /// let audio_subsystem = window.sdl_context().audio().unwrap();
+    /// ```
+    #[cfg(feature = "with-sdl")]
+    pub fn sdl_context(&mut self) -> &sdl2::Sdl {
+        self.sdl_context
+            .get_or_insert_with(|| sdl2::init().unwrap())
+    }
+
     /// Updates the window.
     pub fn update<C>(&mut self, display: &SimulatorDisplay<C>)
     where
         C: PixelColor + Into<Rgb888> + From<Rgb888>,
     {
-        if let Ok(path) = env::var("EG_SIMULATOR_CHECK") {
-            let output = display.to_rgb_output_image(&self.output_settings);
+        self.fps_limiter.start_update();
 
-            let png_file = BufReader::new(File::open(path).unwrap());
-            let expected = image::load(png_file, image::ImageFormat::Png)
-                .unwrap()
-                .to_rgb8();
-
-            let png_size = Size::new(expected.width(), expected.height());
-
-            assert!(
-                output.size().eq(&png_size),
-                "display dimensions don't match PNG dimensions (display: {}x{}, PNG: {}x{})",
-                output.size().width,
-                output.size().height,
-                png_size.width,
-                png_size.height
-            );
+        if let Ok(path) = env::var("EG_SIMULATOR_CHECK") {
+            let output = self.output_image(display);
 
-            assert!(
-                output
-                    .as_image_buffer()
-                    .as_raw()
-                    .eq(&expected.as_raw().deref()),
-                "display content doesn't match PNG file",
-            );
+            check_png(&output, Path::new(&path));
 
             process::exit(0);
         }
@@ -131,10 +325,7 @@ impl Window {
         }
 
         if let Ok(path) = env::var("EG_SIMULATOR_DUMP") {
-            display
-                .to_rgb_output_image(&self.output_settings)
-                .save_png(path)
-                .unwrap();
+            self.output_image(display).save_png(path).unwrap();
             process::exit(0);
         }
 
@@ -148,24 +339,121 @@ impl Window {
 
         #[cfg(feature = "with-sdl")]
         {
-            let size = display.output_size(&self.output_settings);
-
-            if self.framebuffer.is_none() {
+            let output_size = display.output_size(&self.output_settings);
+            let (size, offset) = match &self.bezel {
+                Some(bezel) => (bezel.size(), bezel.display_offset(output_size)),
+                None => (output_size, Point::zero()),
+            };
+            self.display_area = embedded_graphics::primitives::Rectangle::new(offset, output_size);
+
+            if self
+                .framebuffer
+                .as_ref()
+                .map(|framebuffer| framebuffer.size())
+                != Some(size)
+            {
                 self.framebuffer = Some(OutputImage::new(size));
+                self.last_display_id = None;
             }
 
-            if self.sdl_window.is_none() {
-                self.sdl_window = Some(SdlWindow::new(&self.title, size));
+            match &mut self.sdl_window {
+                Some(sdl_window) => {
+                    if sdl_window.size() != size {
+                        sdl_window.set_size(size);
+                    }
+                }
+                None => {
+                    let sdl_context = self.sdl_context().clone();
+                    let mut sdl_window = SdlWindow::new(&sdl_context, &self.title, size, size);
+                    self.window_options.apply(&mut sdl_window);
+                    self.sdl_window = Some(sdl_window);
+                }
             }
 
             let framebuffer = self.framebuffer.as_mut().unwrap();
             let sdl_window = self.sdl_window.as_mut().unwrap();
 
-            framebuffer.draw_display(display, Point::zero(), &self.output_settings);
-            sdl_window.update(framebuffer);
+            // Only the changed part of the display needs to be redrawn if the
+            // same display was shown in the last update.
+            let dirty_area = display.take_window_dirty_area(&mut self.dirty_generation);
+            if self.last_display_id != Some(display.id) {
+                if let Some(bezel) = &self.bezel {
+                    framebuffer.copy_area(
+                        bezel.image(),
+                        &bezel.image().bounding_box(),
+                        Point::zero(),
+                    );
+                }
+                framebuffer.draw_display(display, offset, &self.output_settings);
+                sdl_window.update(framebuffer);
+
+                self.last_display_id = Some(display.id);
+            } else {
+                let output_area = dirty_area
+                    .map(|area| {
+                        framebuffer.draw_display_area(display, offset, &self.output_settings, &area)
+                    })
+                    .unwrap_or_else(embedded_graphics::primitives::Rectangle::zero);
+
+                sdl_window.update_area(framebuffer, &output_area);
+            }
         }
 
         self.fps_limiter.sleep();
+
+        #[cfg(feature = "with-sdl")]
+        if let Some(title) = self.fps_limiter.statistics_title(&self.title) {
+            if let Some(sdl_window) = &mut self.sdl_window {
+                sdl_window.set_title(&title);
+            }
+        }
+    }
+
+    /// Returns the window content for a display.
+    fn output_image<C>(&self, display: &SimulatorDisplay<C>) -> OutputImage<Rgb888>
+    where
+        C: PixelColor + Into<Rgb888>,
+    {
+        match &self.bezel {
+            Some(bezel) => {
+                let mut output = bezel.image().clone();
+                let offset = bezel.display_offset(display.output_size(&self.output_settings));
+                output.draw_display(display, offset, &self.output_settings);
+
+                output
+            }
+            None => display.to_rgb_output_image(&self.output_settings),
+        }
+    }
+
+    /// Sets the device bezel that is shown around the display.
+    ///
+    /// The window size is set to the size of the bezel image and the display is
+    /// drawn centered inside the display area of the bezel. Mouse coordinates
+    /// in events are still translated into display coordinates. Passing `None`
+    /// removes the bezel.
+    ///
+    /// If the window was already shown, it is resized on the next call to
+    /// [`update`](Self::update).
+    ///
+    /// See [`Bezel`] for an example.
+    pub fn set_bezel(&mut self, bezel: Option<Bezel>) {
+        self.bezel = bezel;
+        self.last_display_id = None;
+    }
+
+    /// Returns the canvas layout that is used to convert mouse events.
+    #[cfg(feature = "with-sdl")]
+    fn canvas_layout(&self) -> sdl_window::CanvasLayout {
+        sdl_window::CanvasLayout {
+            display_offset: self.display_area.top_left,
+            display_areas: vec![self.display_area],
+            buttons: self
+                .bezel
+                .iter()
+                .flat_map(|bezel| bezel.buttons().iter().copied())
+                .collect(),
+        }
     }
 
     /// Shows a static display.
@@ -189,6 +477,17 @@ impl Window {
 
     /// Returns an iterator of all captured simulator events.
     ///
+    /// Only events that belong to this window are returned. Events which
+    /// aren't associated with a specific window, like the quit event that is
+    /// generated when the application is asked to terminate, are returned by
+    /// all windows. Closing the window generates a
+    /// [`Quit`](SimulatorEvent::Quit) event for this window only. Touch events
+    /// are returned by the window under the mouse pointer.
+    ///
+    /// Events are queued until they are returned by this method. If the
+    /// events of a window are never polled, only the most recent events are
+    /// kept.
+    ///
     /// # Panics
     ///
     /// Panics if called before [`update`](Self::update) is called at least
@@ -196,14 +495,397 @@ impl Window {
     /// same time.
     #[cfg(feature = "with-sdl")]
     pub fn events(&self) -> SimulatorEventsIter<'_> {
+        self.sdl_window.as_ref().unwrap().events(
+            &self.output_settings,
+            &self.event_settings,
+            self.canvas_layout(),
+        )
+    }
+
+    /// Injects an event into the event queue.
+    ///
+    /// Injected events are returned by [`events`](Self::events) after all
+    /// pending real input events. This can be used for scripted demos and
+    /// automated UI tests. Events can also be pushed while the events
+    /// iterator is used, in which case they are returned by the same iterator.
+    ///
+    /// In combination with the SDL dummy video driver, which is selected by
+    /// setting the `SDL_VIDEODRIVER` environment variable to `dummy`, UI
+    /// tests can also be run on headless machines.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_simulator::{
+    ///     sdl2::MouseButton, OutputSettings, SimulatorDisplay, SimulatorEvent, Window,
+    /// };
+    ///
+    /// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+    /// let mut window = Window::new("Scripted input", &OutputSettings::default());
+    /// window.update(&display);
+    ///
+    /// window.push_event(SimulatorEvent::MouseButtonDown {
+    ///     mouse_btn: MouseButton::Left,
+    ///     point: Point::new(10, 20),
+    /// });
+    ///
+    /// for event in window.events() {
+    ///     // handle event
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`update`](Self::update) is called at least
+    /// once.
+    #[cfg(feature = "with-sdl")]
+    pub fn push_event(&self, event: SimulatorEvent) {
+        self.sdl_window.as_ref().unwrap().push_event(event);
+    }
+
+    /// Returns the current keyboard state.
+    ///
+    /// The keyboard state is updated when events are processed by iterating
+    /// over [`events`](Self::events). Keys that are released while the window
+    /// doesn't have the input focus are automatically reported as released.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`update`](Self::update) is called at least
+    /// once.
+    #[cfg(feature = "with-sdl")]
+    pub fn keyboard_state(&self) -> KeyboardState {
+        self.sdl_window.as_ref().unwrap().keyboard_state()
+    }
+
+    /// Returns the current mouse state.
+    ///
+    /// The mouse position is returned in display coordinates. The mouse state
+    /// is updated when events are processed by iterating over
+    /// [`events`](Self::events).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called before [`update`](Self::update) is called at least
+    /// once.
+    #[cfg(feature = "with-sdl")]
+    pub fn mouse_state(&self) -> MouseState {
         self.sdl_window
             .as_ref()
             .unwrap()
-            .events(&self.output_settings)
+            .mouse_state(&self.output_settings, self.display_area.top_left)
+    }
+
+    /// Sets the key combination that generates a [`Quit`](SimulatorEvent::Quit) event.
+    ///
+    /// By default pressing the Escape key generates a quit event, regardless of
+    /// the held modifier keys. Setting the
+    /// quit key to `None` disables this behavior and all key presses are
+    /// reported as [`KeyDown`](SimulatorEvent::KeyDown) events. Closing the
+    /// window always generates a quit event.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics_simulator::{
+    ///     sdl2::{Keycode, Mod},
+    ///     KeyCombination, OutputSettings, Window,
+    /// };
+    ///
+    /// let mut window = Window::new("Quit with Ctrl+Q", &OutputSettings::default());
+    /// window.set_quit_key(Some(KeyCombination::new(Keycode::Q, Mod::LCTRLMOD)));
+    /// ```
+    #[cfg(feature = "with-sdl")]
+    pub fn set_quit_key(&mut self, quit_key: Option<KeyCombination>) {
+        self.event_settings.quit_key = quit_key;
+    }
+
+    /// Enables or disables the emulation of touch events by the mouse.
+    ///
+    /// If enabled, pressing, moving and releasing the mouse while the left
+    /// button is held generates [`TouchDown`], [`TouchMove`] and [`TouchUp`]
+    /// events in addition to the regular mouse events. If the Ctrl key is held
+    /// when the left mouse button is pressed, a second finger, which is
+    /// mirrored at the center of the window, is emulated. This can be used to
+    /// test pinch gestures.
+    ///
+    /// Touch emulation is disabled by default.
+    ///
+    /// [`TouchDown`]: SimulatorEvent::TouchDown
+    /// [`TouchMove`]: SimulatorEvent::TouchMove
+    /// [`TouchUp`]: SimulatorEvent::TouchUp
+    #[cfg(feature = "with-sdl")]
+    pub fn set_mouse_touch_emulation(&mut self, enabled: bool) {
+        self.event_settings.mouse_touch_emulation = enabled;
+    }
+
+    /// Maps a game controller button to a key.
+    ///
+    /// Pressing and releasing a mapped button generates
+    /// [`KeyDown`](SimulatorEvent::KeyDown) and [`KeyUp`](SimulatorEvent::KeyUp)
+    /// events instead of controller button events. This makes it possible to
+    /// control an application, that only handles keyboard input, with a game
+    /// controller. Buttons that are mapped to the [quit key](Self::set_quit_key)
+    /// generate [`Quit`](SimulatorEvent::Quit) events. Passing `None` as the
+    /// keycode removes the mapping.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics_simulator::{
+    ///     sdl2::{Button, Keycode},
+    ///     OutputSettings, Window,
+    /// };
+    ///
+    /// let mut window = Window::new("Gamepad", &OutputSettings::default());
+    /// window.set_controller_key_mapping(Button::DPadUp, Some(Keycode::Up));
+    /// window.set_controller_key_mapping(Button::DPadDown, Some(Keycode::Down));
+    /// window.set_controller_key_mapping(Button::A, Some(Keycode::Return));
+    /// ```
+    #[cfg(feature = "with-sdl")]
+    pub fn set_controller_key_mapping(
+        &mut self,
+        button: sdl2::controller::Button,
+        keycode: Option<sdl2::keyboard::Keycode>,
+    ) {
+        self.event_settings.set_controller_key(button, keycode);
+    }
+
+    /// Sets the distance the mouse needs to be moved to start a drag.
+    ///
+    /// The threshold is specified in window pixels, which means that it
+    /// doesn't depend on the display scale, and defaults to 3 pixels.
+    /// See [`DragStart`](SimulatorEvent::DragStart) for more details.
+    #[cfg(feature = "with-sdl")]
+    pub fn set_drag_threshold(&mut self, threshold: u32) {
+        self.event_settings.drag_threshold = threshold;
+    }
+
+    /// Sets the window title.
+    ///
+    /// The title can be changed at any time, for example to show the current
+    /// mode of the application.
+    pub fn set_title(&mut self, title: &str) {
+        self.title = String::from(title);
+
+        #[cfg(feature = "with-sdl")]
+        if let Some(sdl_window) = &mut self.sdl_window {
+            sdl_window.set_title(title);
+        }
+    }
+
+    /// Sets the window icon.
+    ///
+    /// [`OutputImage::load_png`] can be used to load the icon from a PNG file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics_simulator::{OutputImage, OutputSettings, Window};
+    ///
+    /// let mut window = Window::new("Icon", &OutputSettings::default());
+    /// window.set_icon(&OutputImage::load_png("icon.png").unwrap());
+    /// ```
+    #[cfg(feature = "with-sdl")]
+    pub fn set_icon(&mut self, icon: &OutputImage<Rgb888>) {
+        if let Some(sdl_window) = &mut self.sdl_window {
+            sdl_window.set_icon(icon);
+        }
+
+        self.window_options.icon = Some(icon.clone());
+    }
+
+    /// Returns the position of the window on the screen.
+    ///
+    /// Returns `None` if the window wasn't shown yet.
+    #[cfg(feature = "with-sdl")]
+    pub fn position(&self) -> Option<Point> {
+        self.sdl_window.as_ref().map(SdlWindow::position)
+    }
+
+    /// Moves the window to a position on the screen.
+    ///
+    /// By default the window is centered on the screen.
+    #[cfg(feature = "with-sdl")]
+    pub fn set_position(&mut self, position: Point) {
+        if let Some(sdl_window) = &mut self.sdl_window {
+            sdl_window.set_position(position);
+        }
+
+        self.window_options.position = Some(position);
+    }
+
+    /// Keeps the window above all other windows.
+    #[cfg(feature = "with-sdl")]
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        if let Some(sdl_window) = &mut self.sdl_window {
+            sdl_window.set_always_on_top(always_on_top);
+        }
+
+        self.window_options.always_on_top = always_on_top;
+    }
+
+    /// Persists the window position in a file.
+    ///
+    /// The window position is saved to the file when a
+    /// [`Quit`](SimulatorEvent::Quit) event is generated and when the window is
+    /// dropped.
+    /// If the file exists when the window is shown, the window is moved to the
+    /// saved position, which takes precedence over
+    /// [`set_position`](Self::set_position). This prevents the window from
+    /// jumping back to the center of the screen every time the application is
+    /// restarted. Errors while reading or writing the file are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics_simulator::{OutputSettings, Window};
+    ///
+    /// let mut window = Window::new("Persistent position", &OutputSettings::default());
+    /// window.set_position_file(std::env::temp_dir().join("my-app-window-position"));
+    /// ```
+    #[cfg(feature = "with-sdl")]
+    pub fn set_position_file<P: AsRef<Path>>(&mut self, path: P) {
+        let path = path.as_ref();
+
+        if let Some(sdl_window) = &mut self.sdl_window {
+            sdl_window.set_position_file(path);
+        }
+
+        self.window_options.position_file = Some(path.to_path_buf());
+    }
+
+    /// Returns the frame rate and timing statistics.
+    ///
+    /// The statistics are updated by every call to [`update`](Self::update).
+    pub fn frame_statistics(&self) -> FrameStatistics {
+        self.fps_limiter.statistics
+    }
+
+    /// Shows the frame rate and timing statistics in the window title.
+    ///
+    /// The title is updated twice per second while the statistics are shown.
+    /// See [`FrameStatistics`] for a description of the values.
+    #[cfg(feature = "with-sdl")]
+    pub fn set_show_frame_statistics(&mut self, show: bool) {
+        if let Some(title) = self.fps_limiter.set_show_statistics(show, &self.title) {
+            if let Some(sdl_window) = &mut self.sdl_window {
+                sdl_window.set_title(&title);
+            }
+        }
+    }
+
+    /// Returns the simulated time.
+    ///
+    /// The simulated time is the time since the window was created and is
+    /// updated by every call to [`update`](Self::update). It doesn't change
+    /// between two updates, which makes it suitable to drive animations.
+    ///
+    /// By default the simulated time follows the real time. If the
+    /// `EG_SIMULATOR_FAST` environment variable is set to `1` or the fast mode
+    /// is enabled with [`set_fast_mode`](Self::set_fast_mode), `update` doesn't
+    /// sleep to limit the frame rate and instead advances the simulated time by
+    /// exactly `1 / max_fps` seconds. This makes animations reproducible and
+    /// fast in tests.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay, Window};
+    ///
+    /// let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+    /// let mut window = Window::new("Animation", &OutputSettings::default());
+    ///
+    /// loop {
+    ///     let t = window.now().as_secs_f32();
+    ///     let x = (t * 32.0) as i32 % 128;
+    ///
+    ///     display.clear(BinaryColor::Off).unwrap();
+    ///     Pixel(Point::new(x, 32), BinaryColor::On)
+    ///         .draw(&mut display)
+    ///         .unwrap();
+    ///
+    ///     window.update(&display);
+    /// }
+    /// ```
+    pub fn now(&self) -> Duration {
+        self.fps_limiter.now
+    }
+
+    /// Enables or disables the fast mode.
+    ///
+    /// The fast mode is enabled by default if the `EG_SIMULATOR_FAST`
+    /// environment variable is set to `1`. See [`now`](Self::now) for more
+    /// details.
+    pub fn set_fast_mode(&mut self, enabled: bool) {
+        self.fps_limiter.set_fast_mode(enabled);
     }
 
     /// Sets the FPS limit of the window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_fps` is `0`.
     pub fn set_max_fps(&mut self, max_fps: u32) {
-        self.fps_limiter.max_fps = max_fps;
+        self.fps_limiter.set_max_fps(max_fps);
+    }
+}
+
+/// Compares an output image with a PNG file.
+///
+/// Used to implement `EG_SIMULATOR_CHECK`.
+pub(crate) fn check_png(output: &OutputImage<Rgb888>, path: &Path) {
+    let png_file = BufReader::new(File::open(path).unwrap());
+    let expected = image::load(png_file, image::ImageFormat::Png)
+        .unwrap()
+        .to_rgb8();
+
+    let png_size = Size::new(expected.width(), expected.height());
+
+    assert!(
+        output.size().eq(&png_size),
+        "display dimensions don't match PNG dimensions (display: {}x{}, PNG: {}x{})",
+        output.size().width,
+        output.size().height,
+        png_size.width,
+        png_size.height
+    );
+
+    assert!(
+        output
+            .as_image_buffer()
+            .as_raw()
+            .eq(&expected.as_raw().deref()),
+        "display content doesn't match PNG file",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_mode_clock() {
+        let mut fps_limiter = FpsLimiter::new();
+        fps_limiter.set_fast_mode(true);
+
+        for _ in 0..600 {
+            fps_limiter.sleep();
+        }
+        assert_eq!(fps_limiter.now, Duration::from_secs(10));
+
+        fps_limiter.set_max_fps(30);
+        for _ in 0..3 {
+            fps_limiter.sleep();
+        }
+        assert_eq!(fps_limiter.now, Duration::from_millis(10_100));
+    }
+
+    #[test]
+    #[should_panic(expected = "max_fps must be greater than 0")]
+    fn zero_max_fps() {
+        FpsLimiter::new().set_max_fps(0);
     }
 }
@@ -1,12 +1,22 @@
-use std::collections::HashMap;
+use std::{cell::Cell, env, path::Path, process, time::Duration};
 
-use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+use sdl2::{controller::Button, keyboard::Keycode, Sdl};
 
 use crate::{
-    window::{sdl_window::SimulatorEventsIter, FpsLimiter, SdlWindow},
-    OutputImage, OutputSettings, SimulatorDisplay,
+    widgets::ButtonArea,
+    window::{
+        check_png,
+        sdl_window::{CanvasLayout, EventSettings, SimulatorEvent, SimulatorEventsIter},
+        FpsLimiter, FrameStatistics, KeyCombination, KeyboardState, MouseState, SdlWindow,
+    },
+    Bezel, DisplayComposition, DisplayHandle, DisplayLayout, Led, OutputImage, OutputSettings,
+    SimulatorDisplay, VirtualButton,
 };
 
+/// Space that is reserved for the window decorations if the window size is limited to the screen size.
+const WINDOW_DECORATION_MARGIN: u32 = 64;
+
 /// Simulator window with support for multiple displays.
 ///
 /// Multiple [`SimulatorDisplay`]s can be added to the window by using the
@@ -14,88 +24,391 @@ use crate::{
 /// are required, first [`update_display`](Self::update_display) needs be called
 /// for all changed displays, then [`flush`](Self::flush) to redraw the window.
 ///
+/// [`add_display`](Self::add_display) returns a [`DisplayHandle`], which can be
+/// used to move, reconfigure or remove the display later. Displays can overlap
+/// each other, the [`z_order`](Self::set_z_order) determines which display is
+/// shown on top.
+///
 /// To determine if the mouse pointer is over one of the displays the
 /// [`translate_mouse_position`](Self::translate_mouse_position) can be used to
 /// translate window coordinates into display coordinates.
+///
+/// # Canvas and view
+///
+/// The size that is passed to [`new`](Self::new) is the size of the window
+/// canvas, which contains the displays. If the canvas is larger than the
+/// screen, the OS window is made smaller and only a part of the canvas is
+/// shown. The visible part of the canvas can be changed by dragging with the
+/// middle mouse button, zoomed by turning the mouse wheel while the Ctrl key
+/// is held and zoomed to fit the window by pressing Ctrl+0. These controls are
+/// only enabled by default if the canvas didn't fit into the window when it
+/// was created, because they consume the middle mouse button, Ctrl+wheel and
+/// Ctrl+0 events. They can be enabled or disabled with
+/// [`set_view_navigation`](Self::set_view_navigation).
+///
+/// The coordinates in mouse events and the mouse state are always canvas
+/// coordinates, which means that the current view is already taken into
+/// account by [`translate_mouse_position`](Self::translate_mouse_position) and
+/// [`display_events`](Self::display_events).
+///
+/// The window content is drawn by a [`DisplayComposition`], which can also be
+/// used without a window. If the `EG_SIMULATOR_DUMP` or `EG_SIMULATOR_CHECK`
+/// environment variables are set, [`flush`](Self::flush) saves or checks the
+/// window content in the same way as [`Window::update`](crate::Window::update).
 pub struct MultiWindow {
     sdl_window: SdlWindow,
-    framebuffer: OutputImage<Rgb888>,
-    displays: HashMap<usize, DisplaySettings>,
+    composition: DisplayComposition,
+    /// Display that receives keyboard events in routed events.
+    focused_display: Cell<Option<DisplayHandle>>,
     fps_limiter: FpsLimiter,
+    event_settings: EventSettings,
+    /// Virtual buttons in canvas coordinates.
+    buttons: Vec<ButtonArea>,
+    title: String,
 }
 
 impl MultiWindow {
     /// Creates a new window with support for multiple displays.
+    ///
+    /// If the `EG_SIMULATOR_CHECK` or `EG_SIMULATOR_DUMP` environment variable
+    /// is set, the SDL dummy video driver is used, because the window content is
+    /// only checked or saved and the window is never shown. This allows the
+    /// check to run in headless CI environments.
     pub fn new(title: &str, size: Size) -> Self {
-        let mut sdl_window = SdlWindow::new(title, size);
+        if env::var_os("EG_SIMULATOR_CHECK").is_some() || env::var_os("EG_SIMULATOR_DUMP").is_some()
+        {
+            sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+        }
 
-        let framebuffer = OutputImage::new(size);
+        Self::with_sdl_context(&sdl2::init().unwrap(), title, size)
+    }
+
+    /// Creates a new window with support for multiple displays using an existing SDL context.
+    ///
+    /// See [`Window::with_sdl_context`](crate::Window::with_sdl_context) for
+    /// more details.
+    pub fn with_sdl_context(sdl_context: &Sdl, title: &str, size: Size) -> Self {
+        let window_size = sdl_context
+            .video()
+            .unwrap()
+            .display_usable_bounds(0)
+            .map(|bounds| {
+                Size::new(
+                    size.width
+                        .min(bounds.width().saturating_sub(WINDOW_DECORATION_MARGIN)),
+                    size.height
+                        .min(bounds.height().saturating_sub(WINDOW_DECORATION_MARGIN)),
+                )
+            })
+            .unwrap_or(size);
+
+        let mut sdl_window = SdlWindow::new(sdl_context, title, size, window_size);
 
-        sdl_window.update(&framebuffer);
+        let mut composition = DisplayComposition::new(size);
+        sdl_window.update(composition.output_image());
+        composition.take_dirty_area();
 
         Self {
-            sdl_window: sdl_window,
-            framebuffer,
-            displays: HashMap::new(),
+            sdl_window,
+            composition,
+            focused_display: Cell::new(None),
             fps_limiter: FpsLimiter::new(),
+            event_settings: EventSettings {
+                view_navigation: window_size != size,
+                ..EventSettings::default()
+            },
+            buttons: Vec::new(),
+            title: String::from(title),
         }
     }
 
     /// Adds a display to the window.
+    ///
+    /// The same display can be added multiple times, for example to show it
+    /// with different output settings. The returned handle identifies this
+    /// instance of the display in the window.
+    ///
+    /// The display is shown after the next call to
+    /// [`update_display`](Self::update_display).
     pub fn add_display<C>(
         &mut self,
         display: &SimulatorDisplay<C>,
         offset: Point,
         output_settings: &OutputSettings,
-    ) {
-        self.displays.insert(
-            display.id,
-            DisplaySettings {
-                offset,
-                output_settings: output_settings.clone(),
-            },
-        );
+    ) -> DisplayHandle {
+        self.composition
+            .add_display(display, offset, output_settings)
+    }
+
+    /// Adds all displays in a layout to the window.
+    ///
+    /// The returned handles are in the same order in which the displays were
+    /// added to the layout. Use [`DisplayLayout::size`] to create a window
+    /// that is large enough to show all displays.
+    pub fn add_layout(&mut self, layout: &DisplayLayout) -> Vec<DisplayHandle> {
+        self.composition.add_layout(layout)
+    }
+
+    /// Sets the caption of a display.
+    ///
+    /// The caption is shown centered below the display. Passing `None` removes
+    /// the caption.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this window.
+    pub fn set_caption(&mut self, handle: DisplayHandle, caption: Option<&str>) {
+        self.composition.set_caption(handle, caption);
+    }
+
+    /// Sets the text color of the display captions.
+    ///
+    /// The default caption color is white.
+    pub fn set_caption_color(&mut self, color: Rgb888) {
+        self.composition.set_caption_color(color);
     }
 
-    /// Fills the internal framebuffer with the given color.
+    /// Removes a display from the window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this window.
+    pub fn remove_display(&mut self, handle: DisplayHandle) {
+        self.composition.remove_display(handle);
+
+        if self.focused_display.get() == Some(handle) {
+            self.focused_display.set(None);
+        }
+    }
+
+    /// Moves a display to a new offset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this window.
+    pub fn set_offset(&mut self, handle: DisplayHandle, offset: Point) {
+        self.composition.set_offset(handle, offset);
+    }
+
+    /// Changes the output settings of a display.
+    ///
+    /// The display is redrawn with the new settings on the next call to
+    /// [`update_display`](Self::update_display). Until then the area of the
+    /// display shows the background.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this window.
+    pub fn set_output_settings(&mut self, handle: DisplayHandle, output_settings: &OutputSettings) {
+        self.composition
+            .set_output_settings(handle, output_settings);
+    }
+
+    /// Sets the z-order of a display.
+    ///
+    /// Displays with a higher z-order are drawn on top of displays with a
+    /// lower z-order. Displays with the same z-order are drawn in the order in
+    /// which they were added. The default z-order is `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this window.
+    pub fn set_z_order(&mut self, handle: DisplayHandle, z_order: i32) {
+        self.composition.set_z_order(handle, z_order);
+    }
+
+    /// Returns the handles of all displays in the window.
+    ///
+    /// The handles are sorted by the z-order, starting with the bottom display.
+    pub fn displays(&self) -> impl Iterator<Item = DisplayHandle> + '_ {
+        self.composition.displays()
+    }
+
+    /// Fills the background of the window with the given color.
     ///
     /// This method can be used to set the background color for the regions of
     /// the window that aren't covered by a display.
     pub fn clear(&mut self, color: Rgb888) {
-        self.framebuffer.clear(color).unwrap();
+        self.composition.clear(color);
+    }
+
+    /// Adds a device bezel to the window.
+    ///
+    /// The bezel image is drawn onto the window background at the given
+    /// position and the bezel buttons are added to the window. The returned
+    /// rectangle is the display area of the bezel in window coordinates, which
+    /// can be used as the offset of the display:
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+    /// use embedded_graphics_simulator::{Bezel, MultiWindow, OutputSettings, SimulatorDisplay};
+    ///
+    /// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+    /// let bezel = Bezel::load_png(
+    ///     "device.png",
+    ///     Rectangle::new(Point::new(40, 30), Size::new(128, 64)),
+    /// )
+    /// .unwrap();
+    ///
+    /// let mut window = MultiWindow::new("Device", bezel.size());
+    /// let display_area = window.add_bezel(&bezel, Point::zero());
+    /// window.add_display(&display, display_area.top_left, &OutputSettings::default());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a bezel button uses the same ID as a button that was already
+    /// added to the window.
+    pub fn add_bezel(&mut self, bezel: &Bezel, position: Point) -> Rectangle {
+        self.composition
+            .draw_background_image(bezel.image(), position);
+
+        for button in bezel.buttons() {
+            self.push_button(ButtonArea {
+                id: button.id,
+                shape: button.shape.translate(position),
+            });
+        }
+
+        bezel.display_area().translate(position)
+    }
+
+    /// Adds a virtual button to the window.
+    ///
+    /// Clicking the button generates
+    /// [`VirtualButtonDown`](SimulatorEvent::VirtualButtonDown) and
+    /// [`VirtualButtonUp`](SimulatorEvent::VirtualButtonUp) events.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Circle};
+    /// use embedded_graphics_simulator::{Led, MultiWindow, SimulatorEvent, VirtualButton};
+    ///
+    /// let mut window = MultiWindow::new("Board", Size::new(200, 100));
+    /// window.add_button(VirtualButton::circle(0, Circle::new(Point::new(20, 20), 20)).label("BTN"));
+    /// window.add_led(Led::new(0, Circle::new(Point::new(80, 26), 8)).label("LED"));
+    ///
+    /// let mut led_on = false;
+    /// loop {
+    ///     window.set_led_color(0, if led_on { Rgb888::GREEN } else { Rgb888::BLACK });
+    ///     window.flush();
+    ///
+    ///     for event in window.events() {
+    ///         match event {
+    ///             SimulatorEvent::VirtualButtonDown { button_id: 0 } => led_on = !led_on,
+    ///             SimulatorEvent::Quit => return,
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a button with the same ID was already added to the window,
+    /// either directly or as part of a bezel.
+    pub fn add_button(&mut self, button: VirtualButton) {
+        self.push_button(button.button_area());
+        self.composition.add_button(button);
+    }
+
+    /// Adds a button area that is used to generate button events.
+    fn push_button(&mut self, button: ButtonArea) {
+        assert!(
+            !self.buttons.iter().any(|other| other.id == button.id),
+            "button ID is already used"
+        );
+
+        self.buttons.push(button);
+    }
+
+    /// Adds an LED indicator to the window.
+    ///
+    /// See [`add_button`](Self::add_button) for an example.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an LED with the same ID was already added.
+    pub fn add_led(&mut self, led: Led) {
+        self.composition.add_led(led);
+    }
+
+    /// Sets the color of an LED.
+    ///
+    /// The LED is only redrawn if the color has changed, which makes it
+    /// possible to call this method in every frame. The change is shown after
+    /// the next call to [`flush`](Self::flush).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no LED with the given ID was added to this window.
+    pub fn set_led_color(&mut self, id: u32, color: Rgb888) {
+        self.composition.set_led_color(id, color);
     }
 
     /// Updates one display.
     ///
+    /// If the display was added multiple times, all instances are updated.
     /// This method only updates the internal framebuffer. Use
     /// [`flush`](Self::flush) after all displays have been updated to finally
     /// update the window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display wasn't added with [`add_display`](Self::add_display).
     pub fn update_display<C>(&mut self, display: &SimulatorDisplay<C>)
     where
         C: PixelColor + Into<Rgb888> + From<Rgb888>,
     {
-        let display_settings = self
-            .displays
-            .get(&display.id)
-            .expect("update_display called for a display that hasn't been added with add_display");
-
-        self.framebuffer.draw_display(
-            display,
-            display_settings.offset,
-            &display_settings.output_settings,
-        );
+        self.composition.update_display(display);
+    }
+
+    /// Returns the window content.
+    pub fn output_image(&self) -> &OutputImage<Rgb888> {
+        self.composition.output_image()
     }
 
     /// Updates the window from the internal framebuffer.
+    ///
+    /// Only the parts of the framebuffer that were changed since the last call
+    /// to this method are uploaded to the window.
+    ///
+    /// If the `EG_SIMULATOR_CHECK` environment variable is set, the window
+    /// content is compared with the PNG file at the given path and the
+    /// process exits after the check. If the `EG_SIMULATOR_DUMP` environment
+    /// variable is set, the window content is saved to a PNG file and the
+    /// process exits.
     pub fn flush(&mut self) {
-        self.sdl_window.update(&self.framebuffer);
+        self.fps_limiter.start_update();
+
+        if let Ok(path) = env::var("EG_SIMULATOR_CHECK") {
+            check_png(self.composition.output_image(), Path::new(&path));
+            process::exit(0);
+        }
+
+        if let Ok(path) = env::var("EG_SIMULATOR_DUMP") {
+            self.composition.output_image().save_png(path).unwrap();
+            process::exit(0);
+        }
+
+        let dirty_area = self
+            .composition
+            .take_dirty_area()
+            .unwrap_or_else(Rectangle::zero);
+        self.sdl_window
+            .update_area(self.composition.output_image(), &dirty_area);
 
         self.fps_limiter.sleep();
+
+        if let Some(title) = self.fps_limiter.statistics_title(&self.title) {
+            self.sdl_window.set_title(&title);
+        }
     }
 
     /// Returns an iterator of all captured simulator events.
     ///
-    /// The coordinates in mouse events are in raw window coordinates, use
+    /// The coordinates in mouse events are in canvas coordinates, use
     /// [`translate_mouse_position`](Self::translate_mouse_position) to
     /// translate them into display coordinates.
     ///
@@ -103,35 +416,379 @@ impl MultiWindow {
     ///
     /// Panics if multiple instances of the iterator are used at the same time.
     pub fn events(&self) -> SimulatorEventsIter<'_> {
-        self.sdl_window.events(&crate::OutputSettings::default())
+        self.sdl_window.events(
+            &OutputSettings::default(),
+            &self.event_settings,
+            CanvasLayout {
+                display_offset: Point::zero(),
+                display_areas: self.composition.display_areas(),
+                buttons: self.buttons.clone(),
+            },
+        )
+    }
+
+    /// Returns an iterator of all captured simulator events routed to the displays.
+    ///
+    /// Each event is tagged with the handle of the display it belongs to and
+    /// the coordinates in the event are translated into the coordinate system
+    /// of this display:
+    ///
+    /// - Mouse and touch events are routed to the topmost display under the
+    ///   cursor. If the cursor isn't over a display, the event isn't routed to
+    ///   a display and the coordinates are in canvas coordinates.
+    /// - Drag events are routed to the display on which the drag was started.
+    ///   The coordinates can be outside of the display area.
+    /// - Keyboard and text input events are routed to the focused display.
+    ///   The focus is moved to a display if a mouse button is pressed while the
+    ///   cursor is over the display.
+    /// - All other events aren't routed to a display.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+    /// use embedded_graphics_simulator::{
+    ///     MultiWindow, OutputSettings, SimulatorDisplay, SimulatorEvent,
+    /// };
+    ///
+    /// let display = SimulatorDisplay::<BinaryColor>::new(Size::new(128, 64));
+    ///
+    /// let mut window = MultiWindow::new("Routed events", Size::new(148, 84));
+    /// let handle = window.add_display(&display, Point::new(10, 10), &OutputSettings::default());
+    ///
+    /// for routed in window.display_events() {
+    ///     if routed.display == Some(handle) {
+    ///         if let SimulatorEvent::MouseButtonDown { point, .. } = routed.event {
+    ///             // `point` is in display coordinates
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if multiple instances of the iterator are used at the same time.
+    pub fn display_events(&self) -> impl Iterator<Item = DisplayEvent> + '_ {
+        self.events().map(|event| self.route_event(event))
+    }
+
+    /// Returns the display that receives keyboard events in routed events.
+    ///
+    /// See [`display_events`](Self::display_events) for more details.
+    pub fn focused_display(&self) -> Option<DisplayHandle> {
+        self.focused_display.get()
+    }
+
+    /// Sets the display that receives keyboard events in routed events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this window.
+    pub fn set_focused_display(&mut self, handle: Option<DisplayHandle>) {
+        if let Some(handle) = handle {
+            self.composition.display_index(handle);
+        }
+
+        self.focused_display.set(handle);
+    }
+
+    fn route_event(&self, mut event: SimulatorEvent) -> DisplayEvent {
+        let display = match &mut event {
+            SimulatorEvent::MouseButtonDown { point, .. } => {
+                let display = self.translate_point(point);
+                if display.is_some() {
+                    self.focused_display.set(display);
+                }
+
+                display
+            }
+            SimulatorEvent::MouseButtonUp { point, .. }
+            | SimulatorEvent::MouseMove { point, .. }
+            | SimulatorEvent::MouseWheel { point, .. }
+            | SimulatorEvent::TouchDown { point, .. }
+            | SimulatorEvent::TouchMove { point, .. }
+            | SimulatorEvent::TouchUp { point, .. } => self.translate_point(point),
+            SimulatorEvent::DragStart {
+                start_point, point, ..
+            }
+            | SimulatorEvent::Drag {
+                start_point, point, ..
+            }
+            | SimulatorEvent::DragEnd {
+                start_point, point, ..
+            } => self.composition.display_at(*start_point).map(|entry| {
+                *start_point = entry.translate_position_unchecked(*start_point);
+                *point = entry.translate_position_unchecked(*point);
+
+                entry.handle
+            }),
+            SimulatorEvent::KeyDown { .. }
+            | SimulatorEvent::KeyUp { .. }
+            | SimulatorEvent::TextInput { .. } => self.focused_display.get(),
+            _ => None,
+        };
+
+        DisplayEvent { display, event }
+    }
+
+    /// Translates a point into the coordinates of the topmost display at this point.
+    ///
+    /// The point is left unchanged if it isn't inside a display.
+    fn translate_point(&self, point: &mut Point) -> Option<DisplayHandle> {
+        let entry = self.composition.display_at(*point)?;
+        *point = entry.translate_position_unchecked(*point);
+
+        Some(entry.handle)
+    }
+
+    /// Returns the SDL context used by this window.
+    ///
+    /// See [`Window::sdl_context`](crate::Window::sdl_context) for more
+    /// details.
+    pub fn sdl_context(&self) -> &Sdl {
+        self.sdl_window.sdl_context()
+    }
+
+    /// Injects an event into the event queue.
+    ///
+    /// Mouse coordinates in injected events should use canvas coordinates.
+    /// See [`Window::push_event`](crate::Window::push_event) for more details.
+    pub fn push_event(&self, event: SimulatorEvent) {
+        self.sdl_window.push_event(event);
+    }
+
+    /// Returns the current keyboard state.
+    ///
+    /// See [`Window::keyboard_state`](crate::Window::keyboard_state) for more
+    /// details.
+    pub fn keyboard_state(&self) -> KeyboardState {
+        self.sdl_window.keyboard_state()
+    }
+
+    /// Returns the current mouse state.
+    ///
+    /// The mouse position is returned in canvas coordinates, use
+    /// [`translate_mouse_position`](Self::translate_mouse_position) to
+    /// translate it into display coordinates.
+    pub fn mouse_state(&self) -> MouseState {
+        self.sdl_window
+            .mouse_state(&OutputSettings::default(), Point::zero())
+    }
+
+    /// Sets the key combination that generates a [`Quit`](crate::SimulatorEvent::Quit) event.
+    ///
+    /// See [`Window::set_quit_key`](crate::Window::set_quit_key) for more
+    /// details.
+    pub fn set_quit_key(&mut self, quit_key: Option<KeyCombination>) {
+        self.event_settings.quit_key = quit_key;
+    }
+
+    /// Enables or disables the emulation of touch events by the mouse.
+    ///
+    /// See [`Window::set_mouse_touch_emulation`](crate::Window::set_mouse_touch_emulation)
+    /// for more details.
+    pub fn set_mouse_touch_emulation(&mut self, enabled: bool) {
+        self.event_settings.mouse_touch_emulation = enabled;
     }
 
     /// Translate a mouse position into display coordinates.
     ///
     /// Returns the corresponding position in the display coordinate system if
-    /// the mouse is inside the display area, otherwise `None` is returned.
+    /// the mouse is inside the display area, otherwise `None` is returned. If
+    /// the display was added multiple times the topmost instance under the
+    /// mouse is used.
     pub fn translate_mouse_position<C>(
         &self,
         display: &SimulatorDisplay<C>,
         position: Point,
     ) -> Option<Point> {
-        let display_settings = self.displays.get(&display.id).expect(
-            "translate_mouse_position called for a display that hasn't been added with add_display",
-        );
+        self.composition.translate_mouse_position(display, position)
+    }
+
+    /// Translate a mouse position into the coordinates of a display instance.
+    ///
+    /// Returns the corresponding position in the display coordinate system if
+    /// the mouse is inside the display area, otherwise `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the display isn't part of this window.
+    pub fn translate_mouse_position_for(
+        &self,
+        handle: DisplayHandle,
+        position: Point,
+    ) -> Option<Point> {
+        self.composition
+            .translate_mouse_position_for(handle, position)
+    }
 
-        let delta = position - display_settings.offset;
-        let p = display_settings.output_settings.output_to_display(delta);
+    /// Maps a game controller button to a key.
+    ///
+    /// See [`Window::set_controller_key_mapping`](crate::Window::set_controller_key_mapping)
+    /// for more details.
+    pub fn set_controller_key_mapping(&mut self, button: Button, keycode: Option<Keycode>) {
+        self.event_settings.set_controller_key(button, keycode);
+    }
 
-        display.bounding_box().contains(p).then_some(p)
+    /// Sets the distance the mouse needs to be moved to start a drag.
+    ///
+    /// The threshold is specified in window pixels, which means that it
+    /// doesn't depend on the view zoom, and defaults to 3 pixels. See
+    /// [`Window::set_drag_threshold`](crate::Window::set_drag_threshold) for
+    /// more details.
+    pub fn set_drag_threshold(&mut self, threshold: u32) {
+        self.event_settings.drag_threshold = threshold;
+    }
+
+    /// Sets the window title.
+    pub fn set_title(&mut self, title: &str) {
+        self.title = String::from(title);
+        self.sdl_window.set_title(title);
+    }
+
+    /// Sets the window icon.
+    ///
+    /// See [`Window::set_icon`](crate::Window::set_icon) for more details.
+    pub fn set_icon(&mut self, icon: &OutputImage<Rgb888>) {
+        self.sdl_window.set_icon(icon);
+    }
+
+    /// Returns the position of the window on the screen.
+    pub fn position(&self) -> Point {
+        self.sdl_window.position()
+    }
+
+    /// Moves the window to a position on the screen.
+    pub fn set_position(&mut self, position: Point) {
+        self.sdl_window.set_position(position);
+    }
+
+    /// Keeps the window above all other windows.
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        self.sdl_window.set_always_on_top(always_on_top);
+    }
+
+    /// Persists the window position in a file.
+    ///
+    /// If the file exists, the window is moved to the saved position. The
+    /// window position is saved to the file when a
+    /// [`Quit`](SimulatorEvent::Quit) event is generated and when the window is
+    /// dropped. See
+    /// [`Window::set_position_file`](crate::Window::set_position_file) for
+    /// more details.
+    pub fn set_position_file<P: AsRef<Path>>(&mut self, path: P) {
+        self.sdl_window.set_position_file(path.as_ref());
+    }
+
+    /// Sets the size of the OS window.
+    ///
+    /// The canvas size isn't changed by this method. If the window is smaller
+    /// than the canvas only a part of the canvas is shown. View navigation
+    /// isn't enabled automatically by this method, use
+    /// [`set_view_navigation`](Self::set_view_navigation) to enable it.
+    pub fn set_window_size(&mut self, size: Size) {
+        self.sdl_window.set_window_size(size);
+    }
+
+    /// Enables or disables panning and zooming of the view with the mouse and keyboard.
+    ///
+    /// View navigation is enabled by default if the canvas is larger than the
+    /// window. See the [type level documentation](Self#canvas-and-view) for
+    /// more details.
+    pub fn set_view_navigation(&mut self, enabled: bool) {
+        self.event_settings.view_navigation = enabled;
+    }
+
+    /// Zooms the view so that the whole canvas is visible.
+    ///
+    /// The change is shown after the next call to [`flush`](Self::flush).
+    pub fn zoom_to_fit(&mut self) {
+        let mut view = self.sdl_window.view();
+        view.zoom_to_fit();
+        self.sdl_window.set_view(view);
+    }
+
+    /// Returns the zoom factor of the view.
+    pub fn zoom(&self) -> f32 {
+        self.sdl_window.view().zoom()
+    }
+
+    /// Sets the zoom factor of the view.
+    ///
+    /// The zoom factor is limited to the range between the factor that fits the
+    /// whole canvas into the window and 8. The top left corner of the window
+    /// stays at the same canvas position. The change is shown after the next
+    /// call to [`flush`](Self::flush).
+    pub fn set_zoom(&mut self, zoom: f32) {
+        let mut view = self.sdl_window.view();
+        view.set_zoom(zoom, Point::zero());
+        self.sdl_window.set_view(view);
+    }
+
+    /// Returns the canvas coordinates of the top left corner of the window.
+    pub fn view_offset(&self) -> Point {
+        self.sdl_window.view().offset()
+    }
+
+    /// Scrolls the view to show the canvas at the given offset in the top left corner of the window.
+    ///
+    /// The offset is limited to keep the view inside the canvas. The change is
+    /// shown after the next call to [`flush`](Self::flush).
+    pub fn set_view_offset(&mut self, offset: Point) {
+        let mut view = self.sdl_window.view();
+        view.set_offset(offset);
+        self.sdl_window.set_view(view);
+    }
+
+    /// Returns the frame rate and timing statistics.
+    ///
+    /// The statistics are updated by every call to [`flush`](Self::flush).
+    pub fn frame_statistics(&self) -> FrameStatistics {
+        self.fps_limiter.statistics
+    }
+
+    /// Shows the frame rate and timing statistics in the window title.
+    ///
+    /// See [`Window::set_show_frame_statistics`](crate::Window::set_show_frame_statistics)
+    /// for more details.
+    pub fn set_show_frame_statistics(&mut self, show: bool) {
+        if let Some(title) = self.fps_limiter.set_show_statistics(show, &self.title) {
+            self.sdl_window.set_title(&title);
+        }
+    }
+
+    /// Returns the simulated time.
+    ///
+    /// The simulated time is updated by every call to [`flush`](Self::flush).
+    /// See [`Window::now`](crate::Window::now) for more details.
+    pub fn now(&self) -> Duration {
+        self.fps_limiter.now
+    }
+
+    /// Enables or disables the fast mode.
+    ///
+    /// See [`Window::now`](crate::Window::now) for more details.
+    pub fn set_fast_mode(&mut self, enabled: bool) {
+        self.fps_limiter.set_fast_mode(enabled);
     }
 
     /// Sets the FPS limit of the window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_fps` is `0`.
     pub fn set_max_fps(&mut self, max_fps: u32) {
-        self.fps_limiter.max_fps = max_fps;
+        self.fps_limiter.set_max_fps(max_fps);
     }
 }
 
-struct DisplaySettings {
-    offset: Point,
-    output_settings: OutputSettings,
+/// Simulator event that is routed to a display.
+///
+/// See [`MultiWindow::display_events`] for more details.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayEvent {
+    /// The display the event is routed to
+    pub display: Option<DisplayHandle>,
+    /// The event with coordinates in the display coordinate system
+    pub event: SimulatorEvent,
 }
@@ -1,23 +1,36 @@
-use std::cell::{RefCell, RefMut};
-
-use embedded_graphics::{
-    pixelcolor::Rgb888,
-    prelude::{Point, Size},
+use std::{
+    cell::{Cell, RefCell, RefMut},
+    collections::{HashMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    rc::{Rc, Weak},
 };
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
 use sdl2::{
-    event::Event,
+    controller::{Axis, Button, GameController},
+    event::{Event, WindowEvent},
     keyboard::{Keycode, Mod},
     mouse::{MouseButton, MouseWheelDirection},
     pixels::PixelFormatEnum,
+    rect::Rect,
     render::{Canvas, Texture, TextureCreator},
-    video::WindowContext,
-    EventPump,
+    surface::Surface,
+    video::{WindowContext, WindowPos},
+    EventPump, GameControllerSubsystem, Sdl,
 };
 
-use crate::{OutputImage, OutputSettings};
+use crate::{
+    widgets::ButtonArea,
+    window::{
+        input_state::{KeyCombination, KeyboardState, MouseButtons, MouseState},
+        view::View,
+    },
+    OutputImage, OutputSettings,
+};
 
 /// A derivation of [`sdl2::event::Event`] mapped to embedded-graphics coordinates
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum SimulatorEvent {
     /// A keypress event, fired on keyUp
     KeyUp {
@@ -57,161 +70,1328 @@ pub enum SimulatorEvent {
         scroll_delta: Point,
         /// The directionality of the scroll (normal or flipped)
         direction: MouseWheelDirection,
+        /// The location of the mouse in Simulator coordinates
+        point: Point,
     },
     /// Mouse move event
     MouseMove {
         /// The current mouse position
         point: Point,
+        /// The mouse buttons that are pressed during the move
+        pressed_buttons: MouseButtons,
+    },
+    /// A mouse drag was started
+    ///
+    /// A drag is started when the mouse is moved further than the drag
+    /// threshold while a mouse button is held down. Only one drag can be active
+    /// at a time, which is bound to the first pressed button.
+    DragStart {
+        /// The mouse button that is held during the drag
+        mouse_btn: MouseButton,
+        /// The location where the mouse button was pressed in Simulator coordinates
+        start_point: Point,
+        /// The current location of the mouse in Simulator coordinates
+        point: Point,
+    },
+    /// The mouse was moved during a drag
+    Drag {
+        /// The mouse button that is held during the drag
+        mouse_btn: MouseButton,
+        /// The location where the mouse button was pressed in Simulator coordinates
+        start_point: Point,
+        /// The current location of the mouse in Simulator coordinates
+        point: Point,
+    },
+    /// A mouse drag was ended by releasing the mouse button
+    DragEnd {
+        /// The mouse button that was held during the drag
+        mouse_btn: MouseButton,
+        /// The location where the mouse button was pressed in Simulator coordinates
+        start_point: Point,
+        /// The location where the mouse button was released in Simulator coordinates
+        point: Point,
+    },
+    /// A touch event, fired when a finger touches the screen
+    ///
+    /// Touch events emulated by the mouse use the finger ID [`MOUSE_FINGER_ID`]
+    /// and [`PINCH_FINGER_ID`] for the second finger in pinch gestures.
+    ///
+    /// [`MOUSE_FINGER_ID`]: crate::MOUSE_FINGER_ID
+    /// [`PINCH_FINGER_ID`]: crate::PINCH_FINGER_ID
+    TouchDown {
+        /// The ID of the finger
+        finger_id: i64,
+        /// The location of the finger in Simulator coordinates
+        point: Point,
+        /// The pressure of the touch in the range `0.0` to `1.0`
+        pressure: f32,
+    },
+    /// A touch event, fired when a finger is moved
+    TouchMove {
+        /// The ID of the finger
+        finger_id: i64,
+        /// The location of the finger in Simulator coordinates
+        point: Point,
+        /// The pressure of the touch in the range `0.0` to `1.0`
+        pressure: f32,
+    },
+    /// A touch event, fired when a finger is lifted
+    TouchUp {
+        /// The ID of the finger
+        finger_id: i64,
+        /// The location of the finger in Simulator coordinates
+        point: Point,
+        /// The pressure of the touch in the range `0.0` to `1.0`
+        pressure: f32,
+    },
+    /// Text input event
+    ///
+    /// Text input events contain the composed text, which takes the keyboard
+    /// layout and input methods into account. Long text can be split into
+    /// multiple events.
+    TextInput {
+        /// The entered text in UTF-8 encoding
+        text: String,
+    },
+    /// The window gained the keyboard focus
+    FocusGained,
+    /// The window lost the keyboard focus
+    ///
+    /// Key up events for keys that are released while the window doesn't have
+    /// the focus aren't reported. Applications that keep track of pressed keys
+    /// should treat all keys as released when this event is received.
+    FocusLost,
+    /// The window was resized
+    WindowResized {
+        /// The new window size in window pixels
+        size: Size,
+    },
+    /// The window was exposed and should be redrawn
+    Exposed,
+    /// A game controller was connected
+    ///
+    /// Controllers that are already connected when the window is created are
+    /// also reported by this event.
+    ControllerAdded {
+        /// The ID of the controller
+        controller_id: u32,
+    },
+    /// A game controller was disconnected
+    ControllerRemoved {
+        /// The ID of the controller
+        controller_id: u32,
+    },
+    /// A game controller button was pressed
+    ControllerButtonDown {
+        /// The ID of the controller
+        controller_id: u32,
+        /// The button being pressed
+        button: Button,
+    },
+    /// A game controller button was released
+    ControllerButtonUp {
+        /// The ID of the controller
+        controller_id: u32,
+        /// The button being released
+        button: Button,
+    },
+    /// A game controller axis was moved
+    ControllerAxisMotion {
+        /// The ID of the controller
+        controller_id: u32,
+        /// The axis being moved
+        axis: Axis,
+        /// The new axis value in the range `-32768..=32767`
+        ///
+        /// Triggers only report positive values.
+        value: i16,
+    },
+    /// A virtual button was pressed
+    ///
+    /// Virtual buttons are clickable areas in the window, for example the
+    /// buttons of a [`Bezel`](crate::Bezel). Pressing the left mouse button
+    /// while the cursor is over a virtual button generates this event instead
+    /// of a mouse button event.
+    VirtualButtonDown {
+        /// The ID of the button
+        button_id: u32,
+    },
+    /// A virtual button was released
+    ///
+    /// This event is generated when the left mouse button is released after a
+    /// [`VirtualButtonDown`](Self::VirtualButtonDown) event, even if the cursor
+    /// was moved outside of the button.
+    VirtualButtonUp {
+        /// The ID of the button
+        button_id: u32,
     },
     /// An exit event
     Quit,
 }
 
+/// Finger ID of touch events that are emulated by the mouse.
+pub const MOUSE_FINGER_ID: i64 = -1;
+
+/// Finger ID of the second finger in emulated pinch gestures.
+pub const PINCH_FINGER_ID: i64 = -2;
+
+/// SDL mouse ID of mouse events that were generated from touch events.
+const TOUCH_MOUSE_ID: u32 = u32::MAX;
+
+/// Zoom factor change per mouse wheel step.
+const WHEEL_ZOOM_FACTOR: f32 = 1.25;
+
+/// Key combination that zooms the view to fit the canvas into the window.
+const ZOOM_TO_FIT_KEY: KeyCombination = KeyCombination::new(Keycode::Num0, Mod::LCTRLMOD);
+
+/// Settings for the conversion of SDL events into simulator events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSettings {
+    /// Generate touch events from left mouse button events.
+    pub mouse_touch_emulation: bool,
+    /// Key combination that generates a quit event.
+    pub quit_key: Option<KeyCombination>,
+    /// Game controller buttons that generate key events.
+    pub controller_keys: HashMap<Button, Keycode>,
+    /// Distance the mouse needs to be moved to start a drag.
+    pub drag_threshold: u32,
+    /// Enable panning and zooming of the view with the mouse and keyboard.
+    pub view_navigation: bool,
+}
+
+impl Default for EventSettings {
+    fn default() -> Self {
+        Self {
+            mouse_touch_emulation: false,
+            quit_key: Some(KeyCombination::any_modifiers(Keycode::Escape)),
+            controller_keys: HashMap::new(),
+            drag_threshold: 3,
+            view_navigation: false,
+        }
+    }
+}
+
+impl EventSettings {
+    /// Adds or removes the key mapping of a game controller button.
+    pub fn set_controller_key(&mut self, button: Button, keycode: Option<Keycode>) {
+        match keycode {
+            Some(keycode) => self.controller_keys.insert(button, keycode),
+            None => self.controller_keys.remove(&button),
+        };
+    }
+}
+
+/// Layout of the window canvas, which is used to convert mouse events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CanvasLayout {
+    /// Offset of the display in the canvas.
+    pub(crate) display_offset: Point,
+    /// Areas of the canvas that are covered by displays.
+    pub(crate) display_areas: Vec<Rectangle>,
+    /// Virtual buttons in canvas coordinates.
+    pub(crate) buttons: Vec<ButtonArea>,
+}
+
+/// State of the event conversion, which is kept between iterators.
+#[derive(Debug, Default)]
+pub struct EventState {
+    /// Converted events that haven't been returned by the iterator yet.
+    queue: VecDeque<SimulatorEvent>,
+    /// Whether a touch is currently emulated by the mouse.
+    mouse_touch: bool,
+    /// Whether a second finger is currently emulated for a pinch gesture.
+    pinch: bool,
+    /// Whether a quit event was already generated because the window was closed.
+    closed: bool,
+    /// Tracks mouse drags.
+    drag: DragTracker,
+    /// ID of the virtual button that is currently pressed.
+    pressed_button: Option<u32>,
+    /// Last mouse position in window coordinates while the view is panned.
+    pan: Option<Point>,
+    /// Keys that generated a quit event and whose key up event is dropped.
+    quit_keys: Vec<Keycode>,
+    /// Window position after the last move, which hasn't been saved yet.
+    moved_position: Option<Point>,
+}
+
+/// Detects mouse drags.
+#[derive(Debug, Default)]
+struct DragTracker {
+    drag: Option<Drag>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Drag {
+    mouse_btn: MouseButton,
+    start_point: Point,
+    /// Start point in window coordinates, which is used for the threshold.
+    start_window_point: Point,
+    started: bool,
+}
+
+impl DragTracker {
+    fn button_down(&mut self, mouse_btn: MouseButton, point: Point, window_point: Point) {
+        if self.drag.is_none() {
+            self.drag = Some(Drag {
+                mouse_btn,
+                start_point: point,
+                start_window_point: window_point,
+                started: false,
+            });
+        }
+    }
+
+    /// Updates the drag after the mouse was moved.
+    ///
+    /// The threshold is compared with the distance in window coordinates,
+    /// which makes it independent of the display scale and the view zoom.
+    fn mouse_move(
+        &mut self,
+        point: Point,
+        window_point: Point,
+        threshold: u32,
+    ) -> Option<SimulatorEvent> {
+        let drag = self.drag.as_mut()?;
+
+        if drag.started {
+            return Some(SimulatorEvent::Drag {
+                mouse_btn: drag.mouse_btn,
+                start_point: drag.start_point,
+                point,
+            });
+        }
+
+        let delta = window_point - drag.start_window_point;
+        let distance_squared = delta.x.pow(2) as u64 + delta.y.pow(2) as u64;
+        if distance_squared < u64::from(threshold).pow(2) {
+            return None;
+        }
+
+        drag.started = true;
+
+        Some(SimulatorEvent::DragStart {
+            mouse_btn: drag.mouse_btn,
+            start_point: drag.start_point,
+            point,
+        })
+    }
+
+    fn button_up(&mut self, mouse_btn: MouseButton, point: Point) -> Option<SimulatorEvent> {
+        let drag = self.drag.filter(|drag| drag.mouse_btn == mouse_btn)?;
+        self.drag = None;
+
+        drag.started.then_some(SimulatorEvent::DragEnd {
+            mouse_btn,
+            start_point: drag.start_point,
+            point,
+        })
+    }
+}
+
+/// Maximum number of events that are queued for a window.
+///
+/// The oldest events are discarded if a window doesn't poll its events.
+const MAX_PENDING_EVENTS: usize = 256;
+
+thread_local! {
+    static EVENT_ROUTER: RefCell<Weak<EventRouter>> = const { RefCell::new(Weak::new()) };
+}
+
+/// Event pump that is shared by all windows.
+///
+/// SDL only allows one event pump to exist at a time. All windows share a
+/// single pump and the router distributes the polled events to the windows
+/// based on the window ID.
+struct EventRouter {
+    sdl_context: Sdl,
+    event_pump: RefCell<EventPump>,
+    /// Game controller subsystem, which is `None` if it couldn't be initialized.
+    game_controller_subsystem: Option<GameControllerSubsystem>,
+    /// Open game controllers, indexed by the instance ID.
+    controllers: RefCell<HashMap<u32, GameController>>,
+    /// Events that were polled, but belong to another window.
+    ///
+    /// The queues are limited to `MAX_PENDING_EVENTS` events, because windows
+    /// whose events are never polled would otherwise grow them indefinitely.
+    pending: RefCell<HashMap<u32, VecDeque<Event>>>,
+    /// Keyboard and mouse state after the last polled event.
+    input_state: RefCell<InputSnapshot>,
+}
+
+/// Snapshot of the keyboard and mouse state.
+///
+/// The state is copied out of the event pump after events are polled, which
+/// allows it to be queried at any time, even while an events iterator exists.
+struct InputSnapshot {
+    pressed_keys: Vec<Keycode>,
+    mouse_state: sdl2::mouse::MouseState,
+}
+
+impl InputSnapshot {
+    fn new(event_pump: &EventPump) -> Self {
+        Self {
+            pressed_keys: event_pump
+                .keyboard_state()
+                .pressed_scancodes()
+                .filter_map(Keycode::from_scancode)
+                .collect(),
+            mouse_state: event_pump.mouse_state(),
+        }
+    }
+}
+
+impl EventRouter {
+    /// Returns the event router, which is created if no window exists.
+    fn get_or_create(sdl_context: &Sdl) -> Rc<Self> {
+        EVENT_ROUTER.with(|cell| {
+            if let Some(router) = cell.borrow().upgrade() {
+                return router;
+            }
+
+            let event_pump = sdl_context.event_pump().unwrap();
+            let router = Rc::new(Self {
+                sdl_context: sdl_context.clone(),
+                input_state: RefCell::new(InputSnapshot::new(&event_pump)),
+                event_pump: RefCell::new(event_pump),
+                game_controller_subsystem: sdl_context.game_controller().ok(),
+                controllers: RefCell::new(HashMap::new()),
+                pending: RefCell::new(HashMap::new()),
+            });
+            *cell.borrow_mut() = Rc::downgrade(&router);
+
+            router
+        })
+    }
+
+    fn register(&self, window_id: u32) {
+        self.pending.borrow_mut().insert(window_id, VecDeque::new());
+    }
+
+    fn unregister(&self, window_id: u32) {
+        self.pending.borrow_mut().remove(&window_id);
+    }
+
+    /// Returns the next event for a window.
+    ///
+    /// Events which aren't associated with a window, like quit events, are
+    /// delivered to all windows. Touch events don't contain a window ID and are
+    /// delivered to the window under the mouse pointer, or to the window with
+    /// the keyboard focus if the pointer isn't inside a window.
+    fn poll_event(&self, window_id: u32) -> Option<Event> {
+        let mut pending = self.pending.borrow_mut();
+
+        if let Some(event) = pending
+            .get_mut(&window_id)
+            .and_then(|queue| queue.pop_front())
+        {
+            return Some(event);
+        }
+
+        let mut event_pump = self.event_pump.borrow_mut();
+        while let Some(event) = event_pump.poll_event() {
+            *self.input_state.borrow_mut() = InputSnapshot::new(&event_pump);
+
+            let Some(event) = self.handle_controller_event(event) else {
+                continue;
+            };
+
+            let target_id = match event {
+                Event::FingerDown { .. } | Event::FingerMotion { .. } | Event::FingerUp { .. } => {
+                    self.sdl_context
+                        .mouse()
+                        .focused_window_id()
+                        .or_else(|| self.sdl_context.keyboard().focused_window_id())
+                }
+                _ => event.get_window_id(),
+            };
+
+            match target_id {
+                Some(id) if id == window_id => return Some(event),
+                Some(id) => {
+                    // Events for windows that aren't managed by the simulator
+                    // are discarded.
+                    if let Some(queue) = pending.get_mut(&id) {
+                        push_pending(queue, event);
+                    }
+                }
+                None => {
+                    for (_, queue) in pending.iter_mut().filter(|(id, _)| **id != window_id) {
+                        push_pending(queue, event.clone());
+                    }
+
+                    return Some(event);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Opens and closes game controllers when they are connected or disconnected.
+    ///
+    /// The device index in added events is replaced by the instance ID, which
+    /// is used by all other controller events. Added events for controllers
+    /// that couldn't be opened are discarded.
+    fn handle_controller_event(&self, event: Event) -> Option<Event> {
+        match event {
+            Event::ControllerDeviceAdded { timestamp, which } => {
+                let controller = self.game_controller_subsystem.as_ref()?.open(which).ok()?;
+                let instance_id = controller.instance_id();
+                self.controllers
+                    .borrow_mut()
+                    .insert(instance_id, controller);
+
+                Some(Event::ControllerDeviceAdded {
+                    timestamp,
+                    which: instance_id,
+                })
+            }
+            Event::ControllerDeviceRemoved { which, .. } => {
+                self.controllers.borrow_mut().remove(&which);
+
+                Some(event)
+            }
+            _ => Some(event),
+        }
+    }
+}
+
+/// Adds an event to a pending event queue and discards the oldest event if the queue is full.
+fn push_pending(queue: &mut VecDeque<Event>, event: Event) {
+    if queue.len() >= MAX_PENDING_EVENTS {
+        queue.pop_front();
+    }
+    queue.push_back(event);
+}
+
 /// Iterator over simulator events.
 ///
 /// See [`Window::events`](crate::Window::events) and
 /// [`MultiWindow::events`](crate::MultiWindow::events) for more details.
 pub struct SimulatorEventsIter<'a> {
-    event_pump: RefMut<'a, EventPump>,
+    event_router: &'a EventRouter,
+    window_id: u32,
+    state: RefMut<'a, EventState>,
+    injected_events: &'a RefCell<VecDeque<SimulatorEvent>>,
     output_settings: OutputSettings,
+    event_settings: &'a EventSettings,
+    layout: CanvasLayout,
+    view: &'a Cell<View>,
+    position_file: Option<&'a Path>,
 }
 
 impl Iterator for SimulatorEventsIter<'_> {
     type Item = SimulatorEvent;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(event) = self.event_pump.poll_event() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => return Some(SimulatorEvent::Quit),
-                Event::KeyDown {
-                    keycode,
-                    keymod,
-                    repeat,
-                    ..
-                } => {
-                    return keycode.map(|valid_keycode| SimulatorEvent::KeyDown {
-                        keycode: valid_keycode,
-                        keymod,
-                        repeat,
-                    })
+        loop {
+            if let Some(event) = self.state.queue.pop_front() {
+                return Some(event);
+            }
+
+            match self.event_router.poll_event(self.window_id) {
+                Some(event) => self.convert_event(event),
+                None => return self.injected_events.borrow_mut().pop_front(),
+            }
+        }
+    }
+}
+
+impl SimulatorEventsIter<'_> {
+    /// Adds a key down event, or a quit event if the key matches the quit key.
+    fn key_down(&mut self, keycode: Keycode, keymod: Mod, repeat: bool) {
+        if self
+            .event_settings
+            .quit_key
+            .is_some_and(|quit_key| quit_key.matches(keycode, keymod))
+        {
+            if !self.state.quit_keys.contains(&keycode) {
+                self.state.quit_keys.push(keycode);
+            }
+            self.quit()
+        } else {
+            self.push(SimulatorEvent::KeyDown {
+                keycode,
+                keymod,
+                repeat,
+            })
+        }
+    }
+
+    /// Adds a quit event.
+    ///
+    /// The window position is saved before the quit event is returned,
+    /// because applications often exit without dropping the window.
+    fn quit(&mut self) {
+        if let (Some(path), Some(position)) = (self.position_file, self.state.moved_position.take())
+        {
+            // The position file is optional state and errors are ignored.
+            save_position(path, position).ok();
+        }
+
+        self.push(SimulatorEvent::Quit)
+    }
+
+    /// Adds a key up event, unless the key down event was converted into a
+    /// quit event.
+    fn key_up(&mut self, keycode: Keycode, keymod: Mod, repeat: bool) {
+        if let Some(index) = self.state.quit_keys.iter().position(|&key| key == keycode) {
+            self.state.quit_keys.swap_remove(index);
+        } else {
+            self.push(SimulatorEvent::KeyUp {
+                keycode,
+                keymod,
+                repeat,
+            })
+        }
+    }
+
+    /// Converts an SDL event and adds the resulting events to the queue.
+    ///
+    /// Events that don't have a corresponding simulator event are ignored.
+    fn convert_event(&mut self, event: Event) {
+        let Some(event) = self.navigate_view(event) else {
+            return;
+        };
+
+        match event {
+            Event::Quit { .. } if !self.state.closed => self.quit(),
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                repeat,
+                ..
+            } => self.key_down(keycode, keymod, repeat),
+            Event::KeyUp {
+                keycode: Some(keycode),
+                keymod,
+                repeat,
+                ..
+            } => self.key_up(keycode, keymod, repeat),
+            Event::MouseButtonUp {
+                x,
+                y,
+                mouse_btn,
+                which,
+                ..
+            } => {
+                if mouse_btn == MouseButton::Left {
+                    if let Some(button_id) = self.state.pressed_button.take() {
+                        self.push(SimulatorEvent::VirtualButtonUp { button_id });
+                        return;
+                    }
                 }
-                Event::KeyUp {
-                    keycode,
-                    keymod,
-                    repeat,
-                    ..
-                } => {
-                    return keycode.map(|valid_keycode| SimulatorEvent::KeyUp {
-                        keycode: valid_keycode,
-                        keymod,
-                        repeat,
-                    })
+
+                let point = self.to_display(Point::new(x, y));
+                self.push(SimulatorEvent::MouseButtonUp { point, mouse_btn });
+
+                if let Some(event) = self.state.drag.button_up(mouse_btn, point) {
+                    self.push(event);
                 }
-                Event::MouseButtonUp {
-                    x, y, mouse_btn, ..
-                } => {
-                    let point = self.output_settings.output_to_display(Point::new(x, y));
-                    return Some(SimulatorEvent::MouseButtonUp { point, mouse_btn });
+
+                if mouse_btn == MouseButton::Left && which != TOUCH_MOUSE_ID {
+                    self.emulate_touch_up(Point::new(x, y));
                 }
-                Event::MouseButtonDown {
-                    x, y, mouse_btn, ..
-                } => {
-                    let point = self.output_settings.output_to_display(Point::new(x, y));
-                    return Some(SimulatorEvent::MouseButtonDown { point, mouse_btn });
+            }
+            Event::MouseButtonDown {
+                x,
+                y,
+                mouse_btn,
+                which,
+                ..
+            } => {
+                // Clicks on virtual buttons aren't reported as mouse events.
+                // The left button stays captured by the virtual button until
+                // it is released, which keeps the drag and touch emulation
+                // state consistent with the reported mouse events.
+                if mouse_btn == MouseButton::Left && self.state.pressed_button.is_none() {
+                    if let Some(button_id) = self.button_at(Point::new(x, y)) {
+                        self.state.pressed_button = Some(button_id);
+                        self.push(SimulatorEvent::VirtualButtonDown { button_id });
+                        return;
+                    }
                 }
-                Event::MouseMotion { x, y, .. } => {
-                    let point = self.output_settings.output_to_display(Point::new(x, y));
-                    return Some(SimulatorEvent::MouseMove { point });
+
+                let point = self.to_display(Point::new(x, y));
+                self.push(SimulatorEvent::MouseButtonDown { point, mouse_btn });
+                self.state
+                    .drag
+                    .button_down(mouse_btn, point, Point::new(x, y));
+
+                if mouse_btn == MouseButton::Left && which != TOUCH_MOUSE_ID {
+                    self.emulate_touch_down(Point::new(x, y));
                 }
-                Event::MouseWheel {
-                    x, y, direction, ..
-                } => {
-                    return Some(SimulatorEvent::MouseWheel {
-                        scroll_delta: Point::new(x, y),
-                        direction,
-                    })
+            }
+            Event::MouseMotion {
+                x, y, mousestate, ..
+            } => {
+                let mut pressed_buttons = MouseButtons::from(mousestate);
+
+                // The left button is captured by a pressed virtual button and
+                // its down event wasn't reported as a mouse event.
+                if self.state.pressed_button.is_some() {
+                    pressed_buttons = pressed_buttons.without(MouseButton::Left);
                 }
-                _ => {
-                    // ignore other events and check next event
+
+                let point = self.to_display(Point::new(x, y));
+                self.push(SimulatorEvent::MouseMove {
+                    point,
+                    pressed_buttons,
+                });
+
+                let threshold = self.event_settings.drag_threshold;
+                if let Some(event) = self
+                    .state
+                    .drag
+                    .mouse_move(point, Point::new(x, y), threshold)
+                {
+                    self.push(event);
                 }
+
+                if pressed_buttons.contains(MouseButton::Left) {
+                    self.emulate_touch_move(Point::new(x, y));
+                }
+            }
+            Event::MouseWheel {
+                x,
+                y,
+                direction,
+                mouse_x,
+                mouse_y,
+                ..
+            } => {
+                let point = self.to_display(Point::new(mouse_x, mouse_y));
+                self.push(SimulatorEvent::MouseWheel {
+                    scroll_delta: Point::new(x, y),
+                    direction,
+                    point,
+                })
+            }
+            Event::TextInput { text, .. } => self.push(SimulatorEvent::TextInput { text }),
+            Event::Window { win_event, .. } => match win_event {
+                WindowEvent::FocusGained => self.push(SimulatorEvent::FocusGained),
+                WindowEvent::FocusLost => self.push(SimulatorEvent::FocusLost),
+                WindowEvent::Resized(width, height) => self.push(SimulatorEvent::WindowResized {
+                    size: Size::new(width.max(0) as u32, height.max(0) as u32),
+                }),
+                WindowEvent::Exposed => self.push(SimulatorEvent::Exposed),
+                WindowEvent::Moved(x, y) => self.state.moved_position = Some(Point::new(x, y)),
+                WindowEvent::Close => {
+                    self.state.closed = true;
+                    self.quit()
+                }
+                _ => {}
+            },
+            Event::ControllerDeviceAdded { which, .. } => {
+                self.push(SimulatorEvent::ControllerAdded {
+                    controller_id: which,
+                })
+            }
+            Event::ControllerDeviceRemoved { which, .. } => {
+                self.push(SimulatorEvent::ControllerRemoved {
+                    controller_id: which,
+                })
+            }
+            Event::ControllerButtonDown { which, button, .. } => {
+                match self.event_settings.controller_keys.get(&button) {
+                    Some(&keycode) => self.key_down(keycode, Mod::NOMOD, false),
+                    None => self.push(SimulatorEvent::ControllerButtonDown {
+                        controller_id: which,
+                        button,
+                    }),
+                }
+            }
+            Event::ControllerButtonUp { which, button, .. } => {
+                match self.event_settings.controller_keys.get(&button) {
+                    Some(&keycode) => self.key_up(keycode, Mod::NOMOD, false),
+                    None => self.push(SimulatorEvent::ControllerButtonUp {
+                        controller_id: which,
+                        button,
+                    }),
+                }
+            }
+            Event::ControllerAxisMotion {
+                which, axis, value, ..
+            } => self.push(SimulatorEvent::ControllerAxisMotion {
+                controller_id: which,
+                axis,
+                value,
+            }),
+            Event::FingerDown {
+                finger_id,
+                x,
+                y,
+                pressure,
+                ..
+            } => {
+                let point = self.touch_point(x, y);
+                self.push(SimulatorEvent::TouchDown {
+                    finger_id,
+                    point,
+                    pressure,
+                });
+            }
+            Event::FingerMotion {
+                finger_id,
+                x,
+                y,
+                pressure,
+                ..
+            } => {
+                let point = self.touch_point(x, y);
+                self.push(SimulatorEvent::TouchMove {
+                    finger_id,
+                    point,
+                    pressure,
+                });
+            }
+            Event::FingerUp {
+                finger_id,
+                x,
+                y,
+                pressure,
+                ..
+            } => {
+                let point = self.touch_point(x, y);
+                self.push(SimulatorEvent::TouchUp {
+                    finger_id,
+                    point,
+                    pressure,
+                });
+            }
+            _ => {
+                // ignore other events
+            }
+        }
+    }
+
+    /// Pans and zooms the view.
+    ///
+    /// Returns the event if it wasn't used to change the view.
+    fn navigate_view(&mut self, event: Event) -> Option<Event> {
+        if !self.event_settings.view_navigation {
+            return Some(event);
+        }
+
+        let mut view = self.view.get();
+
+        match event {
+            Event::MouseButtonDown {
+                x,
+                y,
+                mouse_btn: MouseButton::Middle,
+                ..
+            } => self.state.pan = Some(Point::new(x, y)),
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Middle,
+                ..
+            } if self.state.pan.is_some() => self.state.pan = None,
+            Event::MouseMotion { x, y, .. } if self.state.pan.is_some() => {
+                let point = Point::new(x, y);
+                view.pan(point - self.state.pan.unwrap());
+                self.state.pan = Some(point);
+            }
+            Event::MouseWheel {
+                y,
+                direction,
+                mouse_x,
+                mouse_y,
+                ..
+            } if self.sdl_keymod().intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                let steps = match direction {
+                    MouseWheelDirection::Flipped => -y,
+                    _ => y,
+                };
+                let zoom = view.zoom() * WHEEL_ZOOM_FACTOR.powi(steps);
+                view.set_zoom(zoom, Point::new(mouse_x, mouse_y));
             }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } if ZOOM_TO_FIT_KEY.matches(keycode, keymod) => view.zoom_to_fit(),
+            event => return Some(event),
         }
 
+        self.view.set(view);
+
         None
     }
+
+    fn sdl_keymod(&self) -> Mod {
+        self.event_router.sdl_context.keyboard().mod_state()
+    }
+
+    fn push(&mut self, event: SimulatorEvent) {
+        self.state.queue.push_back(event);
+    }
+
+    /// Converts window coordinates into display coordinates.
+    fn to_display(&self, output_point: Point) -> Point {
+        let canvas_point = self.view.get().window_to_canvas(output_point);
+
+        self.output_settings
+            .output_to_display(canvas_point - self.layout.display_offset)
+    }
+
+    /// Returns the ID of the virtual button at the given window coordinates.
+    fn button_at(&self, output_point: Point) -> Option<u32> {
+        let canvas_point = self.view.get().window_to_canvas(output_point);
+
+        // Displays are drawn on top of the buttons and hide them.
+        if self
+            .layout
+            .display_areas
+            .iter()
+            .any(|area| area.contains(canvas_point))
+        {
+            return None;
+        }
+
+        self.layout
+            .buttons
+            .iter()
+            .rev()
+            .find(|button| button.shape.contains(canvas_point))
+            .map(|button| button.id)
+    }
+
+    /// Converts normalized SDL touch coordinates into display coordinates.
+    fn touch_point(&self, x: f32, y: f32) -> Point {
+        let window_size = self.view.get().window_size();
+        let output_point = Point::new(
+            (x * window_size.width as f32) as i32,
+            (y * window_size.height as f32) as i32,
+        );
+
+        self.to_display(output_point)
+    }
+
+    /// Returns the position of the second finger in an emulated pinch gesture.
+    ///
+    /// The second finger is mirrored at the center of the window.
+    fn pinch_point(&self, output_point: Point) -> Point {
+        let window_size = self.view.get().window_size();
+        let window_size = Point::new(window_size.width as i32, window_size.height as i32);
+
+        self.to_display(window_size - output_point)
+    }
+
+    fn emulate_touch_down(&mut self, output_point: Point) {
+        if !self.event_settings.mouse_touch_emulation {
+            return;
+        }
+
+        let ctrl = {
+            let input_state = self.event_router.input_state.borrow();
+            input_state.pressed_keys.contains(&Keycode::LCtrl)
+                || input_state.pressed_keys.contains(&Keycode::RCtrl)
+        };
+
+        self.state.mouse_touch = true;
+        self.state.pinch = ctrl;
+
+        self.push(SimulatorEvent::TouchDown {
+            finger_id: MOUSE_FINGER_ID,
+            point: self.to_display(output_point),
+            pressure: 1.0,
+        });
+
+        if self.state.pinch {
+            self.push(SimulatorEvent::TouchDown {
+                finger_id: PINCH_FINGER_ID,
+                point: self.pinch_point(output_point),
+                pressure: 1.0,
+            });
+        }
+    }
+
+    fn emulate_touch_move(&mut self, output_point: Point) {
+        if !self.state.mouse_touch {
+            return;
+        }
+
+        self.push(SimulatorEvent::TouchMove {
+            finger_id: MOUSE_FINGER_ID,
+            point: self.to_display(output_point),
+            pressure: 1.0,
+        });
+
+        if self.state.pinch {
+            self.push(SimulatorEvent::TouchMove {
+                finger_id: PINCH_FINGER_ID,
+                point: self.pinch_point(output_point),
+                pressure: 1.0,
+            });
+        }
+    }
+
+    fn emulate_touch_up(&mut self, output_point: Point) {
+        if !self.state.mouse_touch {
+            return;
+        }
+
+        self.push(SimulatorEvent::TouchUp {
+            finger_id: MOUSE_FINGER_ID,
+            point: self.to_display(output_point),
+            pressure: 0.0,
+        });
+
+        if self.state.pinch {
+            self.push(SimulatorEvent::TouchUp {
+                finger_id: PINCH_FINGER_ID,
+                point: self.pinch_point(output_point),
+                pressure: 0.0,
+            });
+        }
+
+        self.state.mouse_touch = false;
+        self.state.pinch = false;
+    }
 }
 
 pub struct SdlWindow {
+    sdl_context: Sdl,
     canvas: Canvas<sdl2::video::Window>,
-    event_pump: RefCell<EventPump>,
+    event_router: Rc<EventRouter>,
+    window_id: u32,
+    event_state: RefCell<EventState>,
+    injected_events: RefCell<VecDeque<SimulatorEvent>>,
     window_texture: SdlWindowTexture,
+    /// Size of the canvas.
     size: Size,
+    view: Cell<View>,
+    /// File the window position is saved to when the window is dropped.
+    position_file: Option<PathBuf>,
 }
 
 impl SdlWindow {
-    pub fn new(title: &str, size: Size) -> Self {
-        let sdl_context = sdl2::init().unwrap();
+    /// Creates a new window.
+    ///
+    /// The canvas size is the size of the framebuffer, which is shown in a
+    /// window of the given window size.
+    pub fn new(sdl_context: &Sdl, title: &str, size: Size, window_size: Size) -> Self {
+        let sdl_context = sdl_context.clone();
         let video_subsystem = sdl_context.video().unwrap();
 
         let window = video_subsystem
-            .window(title, size.width, size.height)
+            .window(title, window_size.width, window_size.height)
             .position_centered()
             .build()
             .unwrap();
 
         let canvas = window.into_canvas().build().unwrap();
-        let event_pump = sdl_context.event_pump().unwrap();
-
-        let window_texture = SdlWindowTextureBuilder {
-            texture_creator: canvas.texture_creator(),
-            texture_builder: |creator: &TextureCreator<WindowContext>| {
-                creator
-                    .create_texture_streaming(PixelFormatEnum::RGB24, size.width, size.height)
-                    .unwrap()
-            },
-        }
-        .build();
+        let window_id = canvas.window().id();
+        let event_router = EventRouter::get_or_create(&sdl_context);
+        event_router.register(window_id);
+
+        let window_texture = create_texture(&canvas, size);
 
         Self {
+            sdl_context,
             canvas,
-            event_pump: RefCell::new(event_pump),
+            event_router,
+            window_id,
+            event_state: RefCell::new(EventState::default()),
+            injected_events: RefCell::new(VecDeque::new()),
             window_texture,
             size,
+            view: Cell::new(View::new(size, window_size)),
+            position_file: None,
         }
     }
 
     pub fn update(&mut self, framebuffer: &OutputImage<Rgb888>) {
-        self.window_texture.with_mut(|fields| {
-            fields
-                .texture
-                .update(
-                    None,
-                    framebuffer.data.as_ref(),
-                    self.size.width as usize * 3,
+        self.update_area(framebuffer, &framebuffer.bounding_box());
+    }
+
+    /// Updates the window with a part of the framebuffer.
+    ///
+    /// Only the given area of the framebuffer is uploaded to the texture.
+    pub fn update_area(&mut self, framebuffer: &OutputImage<Rgb888>, area: &Rectangle) {
+        let area = area.intersection(&framebuffer.bounding_box());
+
+        if !area.is_zero_sized() {
+            let pitch = self.size.width as usize * 3;
+            let start = area.top_left.y as usize * pitch + area.top_left.x as usize * 3;
+            let rect = Rect::new(
+                area.top_left.x,
+                area.top_left.y,
+                area.size.width,
+                area.size.height,
+            );
+
+            self.window_texture.with_mut(|fields| {
+                fields
+                    .texture
+                    .update(rect, &framebuffer.data[start..], pitch)
+                    .unwrap();
+            });
+        }
+
+        self.canvas.set_draw_color(sdl2::pixels::Color::BLACK);
+        self.canvas.clear();
+
+        if let Some((source, destination)) = self.view.get().copy_areas() {
+            self.canvas
+                .copy(
+                    self.window_texture.borrow_texture(),
+                    to_sdl_rect(&source),
+                    to_sdl_rect(&destination),
                 )
                 .unwrap();
-        });
+        }
+
+        self.canvas.present();
+    }
+
+    /// Returns the size of the canvas.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Changes the size of the canvas and resizes the window to the same size.
+    ///
+    /// The window content is undefined until the next update.
+    pub fn set_size(&mut self, size: Size) {
+        self.window_texture = create_texture(&self.canvas, size);
+        self.size = size;
+        self.view.set(View::new(size, size));
 
         self.canvas
-            .copy(self.window_texture.borrow_texture(), None, None)
+            .window_mut()
+            .set_size(size.width, size.height)
             .unwrap();
-        self.canvas.present();
+    }
+
+    /// Returns the view.
+    pub(crate) fn view(&self) -> View {
+        self.view.get()
+    }
+
+    /// Sets the view.
+    ///
+    /// The changed view is shown after the next update.
+    pub(crate) fn set_view(&self, view: View) {
+        self.view.set(view);
+    }
+
+    /// Sets the window title.
+    pub fn set_title(&mut self, title: &str) {
+        self.canvas.window_mut().set_title(title).unwrap();
+    }
+
+    /// Sets the window icon.
+    pub fn set_icon(&mut self, icon: &OutputImage<Rgb888>) {
+        let size = icon.size();
+        let mut data = icon.data.to_vec();
+        let surface = Surface::from_data(
+            &mut data,
+            size.width,
+            size.height,
+            size.width * 3,
+            PixelFormatEnum::RGB24,
+        )
+        .unwrap();
+
+        self.canvas.window_mut().set_icon(surface);
+    }
+
+    /// Returns the position of the window on the screen.
+    pub fn position(&self) -> Point {
+        let (x, y) = self.canvas.window().position();
+
+        Point::new(x, y)
+    }
+
+    /// Moves the window to a position on the screen.
+    pub fn set_position(&mut self, position: Point) {
+        self.canvas.window_mut().set_position(
+            WindowPos::Positioned(position.x),
+            WindowPos::Positioned(position.y),
+        );
+    }
+
+    /// Keeps the window above all other windows.
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        self.canvas.window_mut().set_always_on_top(always_on_top);
+    }
+
+    /// Sets the file that is used to persist the window position.
+    ///
+    /// If the file exists, the window is moved to the position that is stored
+    /// in the file. The position is saved when a quit event is generated after
+    /// the window was moved and when the window is dropped.
+    pub fn set_position_file(&mut self, path: &Path) {
+        if let Some(position) = load_position(path) {
+            self.set_position(position);
+        }
+
+        self.position_file = Some(path.to_path_buf());
+    }
+
+    /// Sets the size of the OS window.
+    pub fn set_window_size(&mut self, window_size: Size) {
+        self.canvas
+            .window_mut()
+            .set_size(window_size.width, window_size.height)
+            .unwrap();
+
+        let mut view = self.view.get();
+        view.set_window_size(window_size);
+        self.view.set(view);
+    }
+
+    /// Returns the SDL context.
+    pub fn sdl_context(&self) -> &Sdl {
+        &self.sdl_context
+    }
+
+    /// Adds an event to the queue of injected events.
+    ///
+    /// Injected events are returned by the events iterator after all events
+    /// from SDL have been returned.
+    pub fn push_event(&self, event: SimulatorEvent) {
+        self.injected_events.borrow_mut().push_back(event);
+    }
+
+    /// Returns the current keyboard state.
+    pub fn keyboard_state(&self) -> KeyboardState {
+        let pressed_keys = self.event_router.input_state.borrow().pressed_keys.clone();
+        let keymod = self.sdl_context.keyboard().mod_state();
+
+        KeyboardState::new(pressed_keys, keymod)
+    }
+
+    /// Returns the current mouse state.
+    ///
+    /// The position is converted into display coordinates by using the given
+    /// output settings and display offset.
+    pub fn mouse_state(
+        &self,
+        output_settings: &OutputSettings,
+        display_offset: Point,
+    ) -> MouseState {
+        let mouse_state = self.event_router.input_state.borrow().mouse_state;
+
+        let canvas_position = self
+            .view
+            .get()
+            .window_to_canvas(Point::new(mouse_state.x(), mouse_state.y()));
+        let position = output_settings.output_to_display(canvas_position - display_offset);
+        MouseState::new(position, mouse_state.into())
     }
 
     /// Handle events
     /// Return an iterator of all captured SimulatorEvent
-    pub fn events(&self, output_settings: &OutputSettings) -> SimulatorEventsIter<'_> {
+    pub fn events<'a>(
+        &'a self,
+        output_settings: &OutputSettings,
+        event_settings: &'a EventSettings,
+        layout: CanvasLayout,
+    ) -> SimulatorEventsIter<'a> {
         SimulatorEventsIter {
-            event_pump: self.event_pump.borrow_mut(),
-            output_settings: output_settings.clone(),
+            event_router: &self.event_router,
+            window_id: self.window_id,
+            state: self.event_state.borrow_mut(),
+            injected_events: &self.injected_events,
+            output_settings: *output_settings,
+            event_settings,
+            layout,
+            view: &self.view,
+            position_file: self.position_file.as_deref(),
+        }
+    }
+}
+
+impl Drop for SdlWindow {
+    fn drop(&mut self) {
+        self.event_router.unregister(self.window_id);
+
+        if let Some(path) = &self.position_file {
+            // The position file is optional state and errors are ignored.
+            save_position(path, self.position()).ok();
+        }
+    }
+}
+
+/// Window settings that are applied when the window is created.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WindowOptions {
+    /// Window icon.
+    pub(crate) icon: Option<OutputImage<Rgb888>>,
+    /// Position of the window on the screen.
+    pub(crate) position: Option<Point>,
+    /// Keep the window above all other windows.
+    pub(crate) always_on_top: bool,
+    /// File that is used to persist the window position.
+    pub(crate) position_file: Option<PathBuf>,
+}
+
+impl WindowOptions {
+    /// Applies the options to a window.
+    pub(crate) fn apply(&self, sdl_window: &mut SdlWindow) {
+        if let Some(icon) = &self.icon {
+            sdl_window.set_icon(icon);
         }
+
+        if let Some(position) = self.position {
+            sdl_window.set_position(position);
+        }
+
+        if self.always_on_top {
+            sdl_window.set_always_on_top(true);
+        }
+
+        if let Some(path) = &self.position_file {
+            sdl_window.set_position_file(path);
+        }
+    }
+}
+
+/// Loads a window position from a file.
+///
+/// Returns `None` if the file doesn't exist or doesn't contain a valid position.
+fn load_position(path: &Path) -> Option<Point> {
+    parse_position(&fs::read_to_string(path).ok()?)
+}
+
+fn parse_position(text: &str) -> Option<Point> {
+    let mut coordinates = text.split_whitespace().map(str::parse);
+
+    match (coordinates.next(), coordinates.next(), coordinates.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Some(Point::new(x, y)),
+        _ => None,
     }
 }
 
+fn save_position(path: &Path, position: Point) -> std::io::Result<()> {
+    fs::write(path, format!("{} {}\n", position.x, position.y))
+}
+
+fn to_sdl_rect(rectangle: &Rectangle) -> Rect {
+    Rect::new(
+        rectangle.top_left.x,
+        rectangle.top_left.y,
+        rectangle.size.width,
+        rectangle.size.height,
+    )
+}
+
+/// Creates a texture for a canvas of the given size.
+fn create_texture(canvas: &Canvas<sdl2::video::Window>, size: Size) -> SdlWindowTexture {
+    SdlWindowTextureBuilder {
+        texture_creator: canvas.texture_creator(),
+        texture_builder: |creator: &TextureCreator<WindowContext>| {
+            creator
+                .create_texture_streaming(PixelFormatEnum::RGB24, size.width, size.height)
+                .unwrap()
+        },
+    }
+    .build()
+}
+
 #[ouroboros::self_referencing]
 struct SdlWindowTexture {
     texture_creator: TextureCreator<WindowContext>,
@@ -219,3 +1399,246 @@ struct SdlWindowTexture {
     #[covariant]
     texture: Texture<'this>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_state_during_event_iteration() {
+        sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+        let sdl_context = sdl2::init().unwrap();
+        let window = SdlWindow::new(&sdl_context, "test", Size::new(4, 4), Size::new(4, 4));
+        window.push_event(SimulatorEvent::Quit);
+
+        let output_settings = OutputSettings::default();
+        let event_settings = EventSettings::default();
+        let mut events = 0;
+        for _ in window.events(&output_settings, &event_settings, CanvasLayout::default()) {
+            // Querying the state must not conflict with the events iterator.
+            assert!(window.keyboard_state().pressed_keys().is_empty());
+            window.mouse_state(&output_settings, Point::zero());
+            events += 1;
+        }
+        assert_eq!(events, 1);
+    }
+
+    fn test_window(size: Size) -> SdlWindow {
+        sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+        let sdl_context = sdl2::init().unwrap();
+        SdlWindow::new(&sdl_context, "test", size, size)
+    }
+
+    /// Converts SDL events and returns the resulting simulator events.
+    fn convert_events(
+        window: &SdlWindow,
+        event_settings: &EventSettings,
+        sdl_events: Vec<Event>,
+    ) -> Vec<SimulatorEvent> {
+        let output_settings = OutputSettings::default();
+        let mut events = window.events(&output_settings, event_settings, CanvasLayout::default());
+        for event in sdl_events {
+            events.convert_event(event);
+        }
+        events.collect()
+    }
+
+    #[test]
+    fn quit_key_release_is_dropped() {
+        let event_settings = EventSettings {
+            quit_key: Some(KeyCombination::new(Keycode::Q, Mod::NOMOD)),
+            ..EventSettings::default()
+        };
+
+        let events = convert_events(
+            &test_window(Size::new(4, 4)),
+            &event_settings,
+            vec![
+                Event::KeyDown {
+                    timestamp: 0,
+                    window_id: 0,
+                    keycode: Some(Keycode::Q),
+                    scancode: None,
+                    keymod: Mod::NOMOD,
+                    repeat: false,
+                },
+                Event::KeyUp {
+                    timestamp: 0,
+                    window_id: 0,
+                    keycode: Some(Keycode::Q),
+                    scancode: None,
+                    keymod: Mod::NOMOD,
+                    repeat: false,
+                },
+            ],
+        );
+        assert_eq!(events, [SimulatorEvent::Quit]);
+    }
+
+    #[test]
+    fn drag_threshold_with_zoom() {
+        let window = test_window(Size::new(40, 40));
+        let mut view = window.view();
+        view.set_zoom(4.0, Point::zero());
+        window.set_view(view);
+
+        let mouse_motion = |x| Event::MouseMotion {
+            timestamp: 0,
+            window_id: 0,
+            which: 0,
+            mousestate: sdl2::mouse::MouseState::from_sdl_state(1),
+            x,
+            y: 0,
+            xrel: 0,
+            yrel: 0,
+        };
+
+        // The threshold is measured in window pixels, even though the mouse
+        // has only moved by a fraction of a canvas pixel.
+        let events = convert_events(
+            &window,
+            &EventSettings::default(),
+            vec![
+                Event::MouseButtonDown {
+                    timestamp: 0,
+                    window_id: 0,
+                    which: 0,
+                    mouse_btn: MouseButton::Left,
+                    clicks: 1,
+                    x: 0,
+                    y: 0,
+                },
+                mouse_motion(2),
+                mouse_motion(3),
+            ],
+        );
+        let drag_events = events
+            .into_iter()
+            .filter(|event| {
+                !matches!(
+                    event,
+                    SimulatorEvent::MouseButtonDown { .. } | SimulatorEvent::MouseMove { .. }
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            drag_events,
+            [SimulatorEvent::DragStart {
+                mouse_btn: MouseButton::Left,
+                start_point: Point::zero(),
+                point: Point::zero(),
+            }]
+        );
+    }
+
+    #[test]
+    fn mapped_controller_quit_button_release_is_dropped() {
+        let mut event_settings = EventSettings::default();
+        event_settings.set_controller_key(Button::Back, Some(Keycode::Escape));
+
+        let events = convert_events(
+            &test_window(Size::new(4, 4)),
+            &event_settings,
+            vec![
+                Event::ControllerButtonDown {
+                    timestamp: 0,
+                    which: 0,
+                    button: Button::Back,
+                },
+                Event::ControllerButtonUp {
+                    timestamp: 0,
+                    which: 0,
+                    button: Button::Back,
+                },
+            ],
+        );
+        assert_eq!(events, [SimulatorEvent::Quit]);
+    }
+
+    #[test]
+    fn pending_events_are_limited() {
+        let mut queue = VecDeque::new();
+        for timestamp in 0..MAX_PENDING_EVENTS as u32 + 10 {
+            push_pending(&mut queue, Event::Quit { timestamp });
+        }
+
+        assert_eq!(queue.len(), MAX_PENDING_EVENTS);
+        assert!(matches!(queue.front(), Some(Event::Quit { timestamp: 10 })));
+    }
+
+    #[test]
+    fn position_file_format() {
+        assert_eq!(parse_position("12 -34\n"), Some(Point::new(12, -34)));
+        assert_eq!(parse_position(""), None);
+        assert_eq!(parse_position("12"), None);
+        assert_eq!(parse_position("12 34 56"), None);
+        assert_eq!(parse_position("a b"), None);
+    }
+
+    #[test]
+    fn drag() {
+        let mut tracker = DragTracker::default();
+
+        assert_eq!(
+            tracker.mouse_move(Point::new(5, 5), Point::new(5, 5), 3),
+            None
+        );
+
+        tracker.button_down(MouseButton::Left, Point::new(10, 10), Point::new(10, 10));
+        assert_eq!(
+            tracker.mouse_move(Point::new(11, 12), Point::new(11, 12), 3),
+            None
+        );
+        assert_eq!(
+            tracker.mouse_move(Point::new(13, 10), Point::new(13, 10), 3),
+            Some(SimulatorEvent::DragStart {
+                mouse_btn: MouseButton::Left,
+                start_point: Point::new(10, 10),
+                point: Point::new(13, 10),
+            })
+        );
+
+        // Other buttons don't affect the active drag.
+        tracker.button_down(MouseButton::Right, Point::new(20, 20), Point::new(20, 20));
+        assert_eq!(
+            tracker.button_up(MouseButton::Right, Point::new(20, 20)),
+            None
+        );
+
+        assert_eq!(
+            tracker.mouse_move(Point::new(15, 10), Point::new(15, 10), 3),
+            Some(SimulatorEvent::Drag {
+                mouse_btn: MouseButton::Left,
+                start_point: Point::new(10, 10),
+                point: Point::new(15, 10),
+            })
+        );
+        assert_eq!(
+            tracker.button_up(MouseButton::Left, Point::new(16, 10)),
+            Some(SimulatorEvent::DragEnd {
+                mouse_btn: MouseButton::Left,
+                start_point: Point::new(10, 10),
+                point: Point::new(16, 10),
+            })
+        );
+        assert_eq!(
+            tracker.mouse_move(Point::new(20, 10), Point::new(20, 10), 3),
+            None
+        );
+    }
+
+    #[test]
+    fn click_without_drag() {
+        let mut tracker = DragTracker::default();
+
+        tracker.button_down(MouseButton::Left, Point::new(10, 10), Point::new(10, 10));
+        assert_eq!(
+            tracker.mouse_move(Point::new(11, 10), Point::new(11, 10), 3),
+            None
+        );
+        assert_eq!(
+            tracker.button_up(MouseButton::Left, Point::new(11, 10)),
+            None
+        );
+    }
+}
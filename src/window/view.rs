@@ -0,0 +1,244 @@
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+/// Maximum zoom factor.
+const MAX_ZOOM: f32 = 8.0;
+
+/// Visible part of the window canvas.
+///
+/// The canvas is the framebuffer of the window, which can be larger than the
+/// OS window. The view determines which part of the canvas is shown and how
+/// window coordinates are translated into canvas coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct View {
+    canvas_size: Size,
+    window_size: Size,
+    /// Canvas coordinates of the top left corner of the window.
+    offset: Point,
+    zoom: f32,
+}
+
+impl View {
+    /// Creates a new view that shows the top left corner of the canvas without zoom.
+    pub(crate) fn new(canvas_size: Size, window_size: Size) -> Self {
+        Self {
+            canvas_size,
+            window_size,
+            offset: Point::zero(),
+            zoom: 1.0,
+        }
+    }
+
+    #[cfg(feature = "with-sdl")]
+    pub(crate) fn window_size(&self) -> Size {
+        self.window_size
+    }
+
+    pub(crate) fn offset(&self) -> Point {
+        self.offset
+    }
+
+    pub(crate) fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Translates window coordinates into canvas coordinates.
+    pub(crate) fn window_to_canvas(&self, point: Point) -> Point {
+        self.offset
+            + Point::new(
+                (point.x as f32 / self.zoom).floor() as i32,
+                (point.y as f32 / self.zoom).floor() as i32,
+            )
+    }
+
+    /// Returns the source area in the canvas and the destination area in the window.
+    ///
+    /// Returns `None` if no part of the canvas is visible.
+    pub(crate) fn copy_areas(&self) -> Option<(Rectangle, Rectangle)> {
+        let source = self
+            .visible_area()
+            .intersection(&Rectangle::new(Point::zero(), self.canvas_size));
+        if source.is_zero_sized() {
+            return None;
+        }
+
+        let top_left = source.top_left - self.offset;
+        let destination = Rectangle::new(
+            Point::new(
+                (top_left.x as f32 * self.zoom).round() as i32,
+                (top_left.y as f32 * self.zoom).round() as i32,
+            ),
+            Size::new(
+                (source.size.width as f32 * self.zoom).round() as u32,
+                (source.size.height as f32 * self.zoom).round() as u32,
+            ),
+        );
+
+        Some((source, destination))
+    }
+
+    /// Sets the size of the window.
+    #[cfg(feature = "with-sdl")]
+    pub(crate) fn set_window_size(&mut self, window_size: Size) {
+        self.window_size = window_size;
+        self.set_zoom(self.zoom, Point::zero());
+    }
+
+    /// Sets the canvas coordinates of the top left corner of the window.
+    ///
+    /// The offset is limited to keep as much of the canvas visible as possible.
+    pub(crate) fn set_offset(&mut self, offset: Point) {
+        let visible_size = self.visible_area().size;
+
+        self.offset = Point::new(
+            clamp_offset(offset.x, visible_size.width, self.canvas_size.width),
+            clamp_offset(offset.y, visible_size.height, self.canvas_size.height),
+        );
+    }
+
+    /// Moves the view by a distance in window pixels.
+    pub(crate) fn pan(&mut self, delta: Point) {
+        let delta = Point::new(
+            (delta.x as f32 / self.zoom).round() as i32,
+            (delta.y as f32 / self.zoom).round() as i32,
+        );
+
+        self.set_offset(self.offset - delta);
+    }
+
+    /// Sets the zoom factor.
+    ///
+    /// The canvas point at the anchor, which is given in window coordinates,
+    /// stays at the same position in the window. The zoom is limited to the
+    /// range between the zoom to fit the canvas into the window and
+    /// [`MAX_ZOOM`].
+    pub(crate) fn set_zoom(&mut self, zoom: f32, anchor: Point) {
+        let anchor_canvas = self.window_to_canvas(anchor);
+
+        self.zoom = zoom.clamp(self.min_zoom(), MAX_ZOOM);
+
+        let anchor_offset = Point::new(
+            (anchor.x as f32 / self.zoom).round() as i32,
+            (anchor.y as f32 / self.zoom).round() as i32,
+        );
+        self.set_offset(anchor_canvas - anchor_offset);
+    }
+
+    /// Zooms the view so that the whole canvas is visible.
+    pub(crate) fn zoom_to_fit(&mut self) {
+        self.zoom = self.fit_zoom();
+        self.set_offset(Point::zero());
+    }
+
+    /// Returns the zoom factor that fits the whole canvas into the window.
+    fn fit_zoom(&self) -> f32 {
+        let zoom_x = self.window_size.width as f32 / self.canvas_size.width.max(1) as f32;
+        let zoom_y = self.window_size.height as f32 / self.canvas_size.height.max(1) as f32;
+
+        zoom_x.min(zoom_y)
+    }
+
+    fn min_zoom(&self) -> f32 {
+        self.fit_zoom().min(1.0)
+    }
+
+    /// Returns the area of the canvas that is covered by the window.
+    fn visible_area(&self) -> Rectangle {
+        Rectangle::new(
+            self.offset,
+            Size::new(
+                (self.window_size.width as f32 / self.zoom).ceil() as u32,
+                (self.window_size.height as f32 / self.zoom).ceil() as u32,
+            ),
+        )
+    }
+}
+
+/// Limits the offset along one axis.
+///
+/// If the canvas is smaller than the visible area it is centered.
+fn clamp_offset(offset: i32, visible_size: u32, canvas_size: u32) -> i32 {
+    let max_offset = canvas_size as i32 - visible_size as i32;
+
+    if max_offset < 0 {
+        max_offset / 2
+    } else {
+        offset.clamp(0, max_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity() {
+        let view = View::new(Size::new(100, 50), Size::new(100, 50));
+
+        assert_eq!(
+            view.window_to_canvas(Point::new(12, 34)),
+            Point::new(12, 34)
+        );
+        assert_eq!(
+            view.copy_areas(),
+            Some((
+                Rectangle::new(Point::zero(), Size::new(100, 50)),
+                Rectangle::new(Point::zero(), Size::new(100, 50))
+            ))
+        );
+    }
+
+    #[test]
+    fn pan() {
+        let mut view = View::new(Size::new(300, 200), Size::new(100, 100));
+
+        view.pan(Point::new(-50, -20));
+        assert_eq!(view.offset(), Point::new(50, 20));
+        assert_eq!(
+            view.window_to_canvas(Point::new(10, 10)),
+            Point::new(60, 30)
+        );
+
+        // The view can't be moved outside of the canvas.
+        view.pan(Point::new(-1000, 1000));
+        assert_eq!(view.offset(), Point::new(200, 0));
+    }
+
+    #[test]
+    fn zoom_to_fit() {
+        let mut view = View::new(Size::new(400, 100), Size::new(200, 100));
+        view.zoom_to_fit();
+
+        assert_eq!(view.zoom(), 0.5);
+        // The canvas is centered vertically.
+        assert_eq!(view.offset(), Point::new(0, -50));
+        assert_eq!(
+            view.window_to_canvas(Point::new(100, 50)),
+            Point::new(200, 50)
+        );
+        assert_eq!(
+            view.copy_areas(),
+            Some((
+                Rectangle::new(Point::zero(), Size::new(400, 100)),
+                Rectangle::new(Point::new(0, 25), Size::new(200, 50))
+            ))
+        );
+    }
+
+    #[test]
+    fn zoom_keeps_anchor() {
+        let mut view = View::new(Size::new(400, 400), Size::new(100, 100));
+        view.set_zoom(2.0, Point::new(50, 50));
+
+        assert_eq!(view.zoom(), 2.0);
+        assert_eq!(
+            view.window_to_canvas(Point::new(50, 50)),
+            Point::new(50, 50)
+        );
+
+        view.set_zoom(100.0, Point::zero());
+        assert_eq!(view.zoom(), MAX_ZOOM);
+
+        view.set_zoom(0.01, Point::zero());
+        assert_eq!(view.zoom(), 0.25);
+    }
+}